@@ -0,0 +1,269 @@
+#ifndef ZERO_FILL_H
+#define ZERO_FILL_H
+
+/* Generated by cbindgen from the zero_fill sources, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/* Former name of ZERO_FILL_FORMAT. */
+#define ZERO_FILL_WAV ZERO_FILL_FORMAT
+
+
+
+typedef enum ZeroFillDetection {
+  /**
+   * Uses the file content, falling back to the file extension when the content is not recognized.
+   */
+  ZERO_FILL_DETECTION_AUTO,
+  /**
+   * Uses only the file content.
+   */
+  ZERO_FILL_DETECTION_CONTENT,
+  /**
+   * Uses only the file extension.
+   */
+  ZERO_FILL_DETECTION_EXTENSION,
+} ZeroFillDetection;
+
+typedef enum ZeroFillMode {
+  /**
+   * Recreates the file with the zero filled content.
+   */
+  ZERO_FILL_MODE_REWRITE,
+  /**
+   * Overwrites the bytes to be zero filled in the existing file, leaving everything else as it is.
+   */
+  ZERO_FILL_MODE_IN_PLACE,
+} ZeroFillMode;
+
+typedef enum ZeroFillRecovery {
+  /**
+   * Fails on WAV files whose sizes do not match the file.
+   */
+  ZERO_FILL_RECOVERY_STRICT,
+  /**
+   * Fills WAV files whose `data` size is 0, 0xFFFFFFFF or past the end of the file up to the end
+   * of the file, as left by a recorder that stopped before writing the sizes.
+   */
+  ZERO_FILL_RECOVERY_LENIENT,
+  /**
+   * Same as `Lenient`, and also corrects the RIFF and `data` sizes of the recovered files.
+   */
+  ZERO_FILL_RECOVERY_REPAIR,
+} ZeroFillRecovery;
+
+/**
+ * Status codes returned by the C functions.
+ */
+typedef enum ZeroFillStatus {
+  ZERO_FILL_OK = 0,
+  /**
+   * The path is not valid UTF-8, which is only required outside of Unix.
+   */
+  ZERO_FILL_C_STR_CONV = 1,
+  ZERO_FILL_FILENAME = 2,
+  ZERO_FILL_IO = 3,
+  /**
+   * The file is malformed.
+   */
+  ZERO_FILL_FORMAT = 4,
+  ZERO_FILL_VERIFY = 5,
+  /**
+   * The file uses a format or encoding that cannot be zero filled.
+   */
+  ZERO_FILL_UNSUPPORTED = 6,
+  /**
+   * A path, path array or range array is NULL.
+   */
+  ZERO_FILL_NULL_POINTER = 7,
+  /**
+   * An argument other than a path is outside of its valid values, such as a time range that ends before it starts.
+   */
+  ZERO_FILL_INVALID_ARGUMENT = 8,
+} ZeroFillStatus;
+
+typedef enum ZeroFillTimeUnit {
+  ZERO_FILL_TIME_UNIT_SECONDS,
+  /**
+   * Sample frames, which must be whole numbers.
+   */
+  ZERO_FILL_TIME_UNIT_FRAMES,
+} ZeroFillTimeUnit;
+
+/**
+ * Options for `zero_fill_with_options`, mirroring `FillOptions`.
+ */
+typedef struct ZeroFillOptions {
+  enum ZeroFillMode mode;
+  bool atomic;
+  /**
+   * 0 uses the default buffer size.
+   */
+  size_t buffer_size;
+  enum ZeroFillDetection detection;
+  bool verify;
+  enum ZeroFillRecovery recovery;
+} ZeroFillOptions;
+
+/**
+ * A span of a recording for `zero_fill_wav_redact`, from `start` up to but not including `end`.
+ */
+typedef struct ZeroFillTimeRange {
+  enum ZeroFillTimeUnit unit;
+  double start;
+  double end;
+} ZeroFillTimeRange;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Zero fills the file using the filler matching its content or extension.
+ *
+ * On Unix the path may hold any bytes, elsewhere it must be UTF-8.
+ *
+ * # Safety
+ *
+ * `file_path` must either be NULL or point to a valid NUL-terminated C string.
+ */
+int32_t zero_fill_matching(const char *file_path);
+
+/**
+ * Same as `zero_fill_matching`, but replaces the file atomically.
+ *
+ * # Safety
+ *
+ * `file_path` must either be NULL or point to a valid NUL-terminated C string.
+ */
+int32_t zero_fill_matching_atomic(const char *file_path);
+
+/**
+ * Same as `zero_fill_matching`, but always uses the WAV filler.
+ *
+ * # Safety
+ *
+ * `file_path` must either be NULL or point to a valid NUL-terminated C string.
+ */
+int32_t zero_fill_wav(const char *file_path);
+
+/**
+ * Same as `zero_fill_matching`, but always zero fills the whole file.
+ *
+ * # Safety
+ *
+ * `file_path` must either be NULL or point to a valid NUL-terminated C string.
+ */
+int32_t zero_fill_any(const char *file_path);
+
+/**
+ * Zero fills the open file behind `fd` in place, using the WAV or AIFF filler if its content is
+ * WAV or AIFF and zeroing all of it otherwise. FLAC content fails with `ZERO_FILL_UNSUPPORTED`,
+ * as it is re-encoded into a shorter file.
+ *
+ * The descriptor must be seekable and open for both reading and writing. It is left open,
+ * at an unspecified offset, and nothing is synced to disk. Only available on Unix.
+ *
+ * # Safety
+ *
+ * `fd` must either be negative or an open file descriptor that is not closed during the call.
+ */
+int32_t zero_fill_fd(int fd);
+
+/**
+ * Returns the options that `zero_fill_matching` uses, to be adjusted before calling `zero_fill_with_options`.
+ */
+struct ZeroFillOptions zero_fill_default_options(void);
+
+/**
+ * Same as `zero_fill_matching`, but with the given options, or the default ones if `options` is NULL.
+ *
+ * # Safety
+ *
+ * `file_path` must either be NULL or point to a valid NUL-terminated C string, and `options`
+ * must either be NULL or point to options with valid `mode`, `detection` and `recovery` values.
+ */
+int32_t zero_fill_with_options(const char *file_path,
+                               const struct ZeroFillOptions *options);
+
+/**
+ * Same as `zero_fill_wav`, but with the given options, or the default ones if `options` is NULL.
+ * `detection` is ignored, as the WAV filler is always used.
+ *
+ * # Safety
+ *
+ * `file_path` must either be NULL or point to a valid NUL-terminated C string, and `options`
+ * must either be NULL or point to options with valid `mode`, `detection` and `recovery` values.
+ */
+int32_t zero_fill_wav_with_options(const char *file_path, const struct ZeroFillOptions *options);
+
+/**
+ * Same as `zero_fill_any`, but with the given options, or the default ones if `options` is NULL.
+ * `detection` and `recovery` are ignored, as the whole file is always zero filled.
+ *
+ * # Safety
+ *
+ * `file_path` must either be NULL or point to a valid NUL-terminated C string, and `options`
+ * must either be NULL or point to options with valid `mode`, `detection` and `recovery` values.
+ */
+int32_t zero_fill_any_with_options(const char *file_path, const struct ZeroFillOptions *options);
+
+/**
+ * Silences only the `count` given ranges of a WAV file, leaving the rest of the audio as it is,
+ * with the given options, or the default ones if `options` is NULL.
+ *
+ * # Safety
+ *
+ * `file_path` must either be NULL or point to a valid NUL-terminated C string, `ranges` must either be NULL
+ * or point to `count` ranges with valid `unit` values, and `options` must either be NULL or point to options
+ * with valid `mode`, `detection` and `recovery` values.
+ */
+int32_t zero_fill_wav_redact(const char *file_path,
+                             const struct ZeroFillTimeRange *ranges,
+                             size_t count,
+                             const struct ZeroFillOptions *options);
+
+/**
+ * Zero fills every file in `file_paths` using `threads` worker threads, or one per CPU if 0.
+ *
+ * The status of each file is written into `statuses` unless it is NULL. Returns `ZERO_FILL_OK`
+ * if every file succeeded, otherwise the status of the first file that failed.
+ *
+ * # Safety
+ *
+ * `file_paths` must either be NULL or point to `count` pointers that are each NULL or point to
+ * a valid NUL-terminated C string, and `statuses` must either be NULL or point to space for `count` statuses.
+ */
+int32_t zero_fill_batch(const char *const *file_paths,
+                        size_t count,
+                        size_t threads,
+                        int32_t *statuses);
+
+/**
+ * Copies the message of the last error on the calling thread into `buf` as a NUL-terminated string,
+ * truncated to fit in `len` bytes.
+ *
+ * Returns the length of the whole message without the NUL, so that a larger buffer can be
+ * passed if it did not fit, or 0 if the last call on the calling thread succeeded.
+ *
+ * # Safety
+ *
+ * `buf` must either be NULL or point to space for `len` bytes.
+ */
+size_t zero_fill_last_error_message(char *buf, size_t len);
+
+/**
+ * Returns the OS error number behind the last error on the calling thread,
+ * or 0 if the last call succeeded or did not fail because of the OS.
+ */
+int32_t zero_fill_last_os_error(void);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* ZERO_FILL_H */
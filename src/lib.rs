@@ -1,3 +1,9 @@
+#[cfg(test)]
+extern crate cbindgen;
+#[cfg(test)]
+extern crate claxon;
+extern crate glob;
 extern crate hound;
+extern crate md5;
 
 pub mod zero_fill;
\ No newline at end of file
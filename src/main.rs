@@ -0,0 +1,376 @@
+extern crate glob;
+extern crate zero_fill;
+
+use glob::Pattern;
+use zero_fill::zero_fill::aiff;
+use zero_fill::zero_fill::flac;
+use zero_fill::zero_fill::detect::Detection;
+use zero_fill::zero_fill::error;
+use zero_fill::zero_fill::plan::{self, Plan};
+use zero_fill::zero_fill::redact::{self, TimeRange};
+use zero_fill::zero_fill::walk::{self, SymlinkPolicy, WalkOptions};
+use zero_fill::zero_fill::{self as zf, FillOptions, Mode, Recovery};
+
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const EXIT_USAGE: i32 = 64;
+
+const USAGE: &str = "usage: zero_fill [options] <path>...
+
+Zero fills each of the given files, keeping the file sizes, except for FLAC files,
+whose audio is re-encoded as shorter silence.
+
+options:
+    --mode <matching|wav|aiff|flac|any>
+                                filler to use (default: matching)
+    --detect <auto|content|extension>
+                                how the matching mode picks the filler (default: auto)
+    --in-place                  overwrite the existing file data instead of recreating the file
+    --atomic                    write to a temporary file and rename it over the original
+    --buffer-size <bytes>       size of the buffer used to write the zeroes
+    --verify                    re-read every file after filling it and fail if it is not fully filled
+    --recovery <strict|lenient|repair>
+                                how WAV files with wrong sizes, e.g. from a crashed recorder, are filled:
+                                rejected, filled up to the end of the file, or filled and their sizes
+                                corrected (default: strict)
+    --redact <start>-<end>      only silence this span of a WAV file, in seconds or [hh:]mm:ss[.s],
+                                e.g. 01:12.5-01:20 (repeatable)
+    --redact-frames <start>-<end>
+                                same as --redact, in sample frames (repeatable)
+    -n, --dry-run               print what would be overwritten without touching the files
+    -r, --recursive             fill every file under the given directories
+    --include <pattern>         with -r, only fill files matching the glob pattern (repeatable)
+    --exclude <pattern>         with -r, skip files and directories matching the glob pattern (repeatable)
+    --symlinks <follow|skip|error>
+                                with -r, how symbolic links are treated (default: skip)
+    -h, --help                  print this help
+
+The exit code is 0 on success, otherwise the ZERO_FILL_* status code of the first failure.";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Filler {
+    Matching,
+    Wav,
+    Aiff,
+    Flac,
+    Any,
+}
+
+#[derive(Debug)]
+struct Args {
+    filler: Filler,
+    options: FillOptions,
+    dry_run: bool,
+    recursive: bool,
+    walk_options: WalkOptions,
+    /// Spans to silence instead of the whole file.
+    ranges: Vec<TimeRange>,
+    paths: Vec<PathBuf>,
+}
+
+enum Parsed {
+    Run(Args),
+    Help,
+}
+
+fn parse_args<I: IntoIterator<Item = OsString>>(args: I) -> Result<Parsed, String> {
+    let mut args = args.into_iter();
+
+    let mut filler = Filler::Matching;
+    let mut options = FillOptions::default();
+    let mut dry_run = false;
+    let mut recursive = false;
+    let mut walk_options = WalkOptions::default();
+    let mut ranges = Vec::new();
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next()
+            .ok_or_else(|| format!("missing value for {}", name))?
+            .into_string()
+            .map_err(|_| format!("invalid value for {}", name));
+
+        // paths need not be valid UTF-8, so only options are converted
+        match arg.to_str().unwrap_or_default() {
+            "-h" | "--help" => return Ok(Parsed::Help),
+
+            "--mode" => filler = match value("--mode")?.as_ref() {
+                "matching" => Filler::Matching,
+                "wav" => Filler::Wav,
+                "aiff" => Filler::Aiff,
+                "flac" => Filler::Flac,
+                "any" => Filler::Any,
+                other => return Err(format!("unknown mode: {}", other)),
+            },
+
+            "--detect" => options.detection = match value("--detect")?.as_ref() {
+                "auto" => Detection::Auto,
+                "content" => Detection::Content,
+                "extension" => Detection::Extension,
+                other => return Err(format!("unknown detection: {}", other)),
+            },
+
+            "--recovery" => options.recovery = match value("--recovery")?.as_ref() {
+                "strict" => Recovery::Strict,
+                "lenient" => Recovery::Lenient,
+                "repair" => Recovery::Repair,
+                other => return Err(format!("unknown recovery: {}", other)),
+            },
+
+            "--in-place" => options.mode = Mode::InPlace,
+            "--atomic" => options.atomic = true,
+            "--verify" => options.verify = true,
+
+            "--buffer-size" => options.buffer_size = match value("--buffer-size")?.parse() {
+                Ok(size) if size > 0 => size,
+                _ => return Err("--buffer-size must be a positive number of bytes".to_owned()),
+            },
+
+            "--redact" => ranges.push(time_range(&value("--redact")?, seconds, TimeRange::Seconds)?),
+            "--redact-frames" => ranges.push(time_range(&value("--redact-frames")?, |s| s.parse().ok(), TimeRange::Frames)?),
+
+            "-n" | "--dry-run" => dry_run = true,
+            "-r" | "--recursive" => recursive = true,
+
+            "--include" => walk_options.include.push(pattern(&value("--include")?)?),
+            "--exclude" => walk_options.exclude.push(pattern(&value("--exclude")?)?),
+
+            "--symlinks" => walk_options.symlinks = match value("--symlinks")?.as_ref() {
+                "follow" => SymlinkPolicy::Follow,
+                "skip" => SymlinkPolicy::Skip,
+                "error" => SymlinkPolicy::Error,
+                other => return Err(format!("unknown symlink policy: {}", other)),
+            },
+
+            "--" => paths.extend(args.by_ref().map(PathBuf::from)),
+            opt if opt.starts_with('-') => return Err(format!("unknown option: {}", opt)),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if paths.is_empty() {
+        return Err("no paths given".to_owned());
+    }
+
+    if !ranges.is_empty() && filler != Filler::Matching && filler != Filler::Wav {
+        return Err("--redact only works with WAV files".to_owned());
+    }
+
+    Ok(Parsed::Run(Args { filler, options, dry_run, recursive, walk_options, ranges, paths }))
+}
+
+/// Parses a `<start>-<end>` range, converting both bounds with `bound`.
+fn time_range<T, B, R>(value: &str, bound: B, range: R) -> Result<TimeRange, String>
+    where B: Fn(&str) -> Option<T>, R: FnOnce(T, T) -> TimeRange {
+
+    let mut bounds = value.splitn(2, '-').map(|s| bound(s.trim()));
+
+    match (bounds.next(), bounds.next()) {
+        (Some(Some(start)), Some(Some(end))) => Ok(range(start, end)),
+        _ => Err(format!("invalid time range: {}", value)),
+    }
+}
+
+/// Parses seconds, given either as a number or as `[hh:]mm:ss[.s]`.
+fn seconds(value: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+
+    for (i, part) in value.split(':').enumerate() {
+        if i > 2 {
+            return None;
+        }
+
+        let part: f64 = part.parse().ok().filter(|n: &f64| n.is_finite() && *n >= 0.0)?;
+        seconds = seconds * 60.0 + part;
+    }
+
+    Some(seconds)
+}
+
+fn pattern(value: &str) -> Result<Pattern, String> {
+    Pattern::new(value).map_err(|e| format!("invalid pattern {}: {}", value, e))
+}
+
+fn fill(args: &Args, path: &Path) -> error::Result<()> {
+    if !args.ranges.is_empty() {
+        if args.dry_run {
+            print_plan(path, &redact::plan_redact_wav(path, &args.ranges, &args.options)?);
+            return Ok(());
+        }
+
+        return redact::redact_wav_with(path, &args.ranges, &args.options);
+    }
+
+    if args.dry_run {
+        let plan = match args.filler {
+            Filler::Matching => plan::plan(path, &args.options)?,
+            Filler::Wav => zf::plan_wav_with(path, &args.options)?,
+            Filler::Aiff => aiff::plan_aiff(path)?,
+            Filler::Flac => flac::plan_flac(path)?,
+            Filler::Any => zf::plan_any(path)?,
+        };
+
+        print_plan(path, &plan);
+        return Ok(());
+    }
+
+    match args.filler {
+        Filler::Matching => zf::fill_matching_with(path, &args.options),
+        Filler::Wav => zf::fill_wav_with(path, &args.options),
+        Filler::Aiff => aiff::fill_aiff_with(path, &args.options),
+        Filler::Flac => flac::fill_flac_with(path, &args.options),
+        Filler::Any => zf::fill_any_with(path, &args.options),
+    }
+}
+
+fn print_plan(path: &Path, plan: &Plan) {
+    println!("{}: {} filler, {} bytes overwritten, {} bytes after filling",
+        path.display(), plan.filler, plan.overwritten(), plan.len);
+
+    for region in &plan.regions {
+        println!("    {}..{} with 0x{:02X}", region.range.start, region.range.end, region.fill);
+    }
+}
+
+fn run(args: &Args) -> i32 {
+    let mut exit_code = zf::ZERO_FILL_OK;
+
+    let mut report_failure = |path: &Path, e: &error::Error| {
+        if e.path().is_some() {
+            eprintln!("zero_fill: {}", e);
+        } else {
+            eprintln!("zero_fill: {}: {}", path.display(), e);
+        }
+
+        if exit_code == zf::ZERO_FILL_OK {
+            exit_code = zf::status_code(e);
+        }
+    };
+
+    for path in &args.paths {
+        if args.recursive {
+            let report = walk::fill_dir_with(path, &args.walk_options, |file_path| fill(args, file_path));
+            let failures = report.failures();
+
+            for &(file_path, e) in &failures {
+                report_failure(file_path, e);
+            }
+
+            println!("{}: {} {}, {} failed, {} skipped",
+                path.display(), report.results.len() - failures.len(), if args.dry_run { "planned" } else { "filled" },
+                failures.len(), report.skipped.len());
+        } else if let Err(e) = fill(args, path) {
+            report_failure(path, &e);
+        }
+    }
+
+    exit_code
+}
+
+fn main() {
+    let exit_code = match parse_args(env::args_os().skip(1)) {
+        Ok(Parsed::Run(args)) => run(&args),
+
+        Ok(Parsed::Help) => {
+            println!("{}", USAGE);
+            zf::ZERO_FILL_OK
+        },
+
+        Err(msg) => {
+            eprintln!("zero_fill: {}\n\n{}", msg, USAGE);
+            EXIT_USAGE
+        },
+    };
+
+    process::exit(exit_code);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Parsed, String> {
+        parse_args(args.iter().map(OsString::from))
+    }
+
+    #[test]
+    pub fn test_parse_args() {
+        let args = match parse(&["--mode", "wav", "--in-place", "--verify", "--buffer-size", "4096", "--recovery", "repair",
+            "a.wav", "--", "-b.wav"]) {
+            Ok(Parsed::Run(args)) => args,
+            _ => panic!("expected arguments to run with"),
+        };
+
+        assert_eq!(Filler::Wav, args.filler);
+        assert_eq!(Mode::InPlace, args.options.mode);
+        assert_eq!(4096, args.options.buffer_size);
+        assert!(!args.options.atomic);
+        assert!(args.options.verify);
+        assert_eq!(Recovery::Repair, args.options.recovery);
+        assert!(!args.dry_run);
+        assert_eq!(vec![PathBuf::from("a.wav"), PathBuf::from("-b.wav")], args.paths);
+    }
+
+    #[test]
+    pub fn test_parse_args_recursive() {
+        let args = match parse(&["-n", "-r", "--include", "*.wav", "--exclude", "tmp", "--symlinks", "follow", "data"]) {
+            Ok(Parsed::Run(args)) => args,
+            _ => panic!("expected arguments to run with"),
+        };
+
+        assert!(args.recursive);
+        assert!(args.dry_run);
+        assert_eq!(vec![Pattern::new("*.wav").unwrap()], args.walk_options.include);
+        assert_eq!(vec![Pattern::new("tmp").unwrap()], args.walk_options.exclude);
+        assert_eq!(SymlinkPolicy::Follow, args.walk_options.symlinks);
+        assert!(parse(&["-r", "--include", "[", "data"]).is_err());
+    }
+
+    #[test]
+    pub fn test_parse_args_redact() {
+        let args = match parse(&["--redact", "01:12.5-01:20", "--redact", "1:00:00-3600.5", "--redact-frames", "100-200", "a.wav"]) {
+            Ok(Parsed::Run(args)) => args,
+            _ => panic!("expected arguments to run with"),
+        };
+
+        assert_eq!(vec![
+            TimeRange::Seconds(72.5, 80.0),
+            TimeRange::Seconds(3600.0, 3600.5),
+            TimeRange::Frames(100, 200),
+        ], args.ranges);
+
+        assert!(parse(&["--redact", "72.5", "a.wav"]).is_err());
+        assert!(parse(&["--redact", "1:2:3:4-5", "a.wav"]).is_err());
+        assert!(parse(&["--redact-frames", "1.5-2", "a.wav"]).is_err());
+        assert!(parse(&["--mode", "any", "--redact", "0-1", "a.wav"]).is_err());
+    }
+
+    #[test]
+    pub fn test_parse_args_errors() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["--mode", "mp3", "a"]).is_err());
+        assert!(parse(&["--buffer-size", "0", "a"]).is_err());
+        assert!(parse(&["--buffer-size"]).is_err());
+        assert!(parse(&["--recovery", "always", "a"]).is_err());
+        assert!(parse(&["--unknown", "a"]).is_err());
+        assert!(matches!(parse(&["--help"]), Ok(Parsed::Help)));
+    }
+
+    #[test]
+    pub fn test_run_exit_code() {
+        let args = Args {
+            filler: Filler::Wav,
+            options: FillOptions::default(),
+            dry_run: false,
+            recursive: false,
+            walk_options: WalkOptions::default(),
+            ranges: Vec::new(),
+            paths: vec![PathBuf::from("test_run_exit_code_missing.wav")],
+        };
+
+        assert_eq!(zf::ZERO_FILL_IO, run(&args));
+    }
+}
@@ -0,0 +1,292 @@
+use super::error::{self, Error, Operation, ResultExt};
+use super::plan::{Plan, Region};
+use super::registry::AiffFiller;
+use super::riff::{self, ByteOrder, Sizes};
+use super::verify::{self, Verification};
+use super::{fill_regions, write_repeated, FillOptions};
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::Path;
+
+const COMM_LEN: usize = 18;
+const COMM_AIFC_LEN: usize = 22;
+/// The offset and block size fields that precede the sound data in the SSND chunk.
+const SSND_HEADER_LEN: u64 = 8;
+
+/// The fields of the COMM chunk that are needed to find and silence the sound data.
+#[derive(Clone, Debug, PartialEq)]
+struct Comm {
+    channels: u16,
+    frames: u32,
+    bits: u16,
+    rate: f64,
+    /// `NONE` for AIFF, which has no compression type field.
+    compression: [u8; 4],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Aiff {
+    is_aifc: bool,
+    comm: Comm,
+    /// Byte range of the sample frames, which excludes the SSND offset and block padding.
+    sound: Range<u64>,
+    silence: u8,
+}
+
+pub fn fill_aiff<P: AsRef<Path>>(file_path: P) -> error::Result<()> {
+    fill_aiff_with(file_path, &FillOptions::default())
+}
+
+/// Replaces the sample frames of an AIFF or AIFF-C file with silence, keeping every other chunk as it is.
+pub fn fill_aiff_with<P: AsRef<Path>>(file_path: P, options: &FillOptions) -> error::Result<()> {
+    verify::fill_verified(&AiffFiller, file_path.as_ref(), options)
+}
+
+pub fn fill_aiff_unverified(file_path: &Path, options: &FillOptions) -> error::Result<()> {
+    // planned before anything is written so that unsupported files are left untouched
+    let plan = plan_aiff(file_path)?;
+    fill_regions(file_path, &plan.regions, &[], options)
+}
+
+/// Same as `fill_aiff_with`, but for an open stream such as a file or `Cursor`.
+///
+/// The stream is always filled in place and is not verified, so only `options.buffer_size` is used.
+pub fn fill_aiff_stream<S: Read + Write + Seek>(stream: &mut S, options: &FillOptions) -> error::Result<()> {
+    let aiff = read_aiff(stream).during(Operation::ReadHeader)?;
+
+    stream.seek(SeekFrom::Start(aiff.sound.start)).during(Operation::Write)?;
+    write_repeated(stream, aiff.silence, aiff.sound.end - aiff.sound.start, options.buffer_size).during(Operation::Write)?;
+    stream.flush().during(Operation::Finalize)
+}
+
+pub fn plan_aiff<P: AsRef<Path>>(file_path: P) -> error::Result<Plan> {
+    let file_path = file_path.as_ref();
+
+    let plan = || -> error::Result<Plan> {
+        let mut file = File::open(file_path).during(Operation::Open)?;
+        let aiff = read_aiff(&mut file).during(Operation::ReadHeader)?;
+
+        Ok(Plan {
+            filler: "aiff".to_owned(),
+            regions: vec![Region { range: aiff.sound.clone(), fill: aiff.silence }],
+            len: file.metadata().during(Operation::Read)?.len(),
+            structure: Some(describe(&aiff)),
+        })
+    };
+
+    plan().for_path(file_path)
+}
+
+/// Verifies the filled AIFF file against a plan made before filling, requiring the COMM chunk
+/// to be unchanged and the sample frames to hold only silence.
+pub fn verify_aiff<P: AsRef<Path>>(file_path: P, plan: &Plan) -> error::Result<Verification> {
+    verify::verify_replanned(file_path, plan, |file_path| plan_aiff(file_path))
+}
+
+fn read_aiff<R: Read + Seek>(reader: &mut R) -> error::Result<Aiff> {
+    let mut is_aifc = false;
+
+    let chunks = riff::read_form(reader, ByteOrder::Big, |header| {
+        is_aifc = &header[8..12] == b"AIFC";
+
+        if &header[0..4] != b"FORM" || (&header[8..12] != b"AIFF" && !is_aifc) {
+            return Err(riff::format_error("not an AIFF or AIFF-C file"));
+        }
+
+        Ok(Sizes::Header)
+    })?;
+
+    let comm = read_comm(&riff::read_payload(reader, riff::find(&chunks, b"COMM")?)?, is_aifc)?;
+    let (sample_len, silence) = encoding(&comm)?;
+
+    let ssnd = riff::find(&chunks, b"SSND")?.payload();
+
+    if ssnd.end - ssnd.start < SSND_HEADER_LEN {
+        return Err(riff::format_error("truncated SSND chunk"));
+    }
+
+    let mut ssnd_header = [0u8; SSND_HEADER_LEN as usize];
+    reader.seek(SeekFrom::Start(ssnd.start))?;
+    reader.read_exact(&mut ssnd_header)?;
+
+    // the sound data starts `offset` bytes in, which is how it gets aligned to `block_size`
+    let offset = u64::from(ByteOrder::Big.u32(&ssnd_header[0..4]));
+    let start = ssnd.start + SSND_HEADER_LEN + offset;
+
+    if start > ssnd.end {
+        return Err(riff::format_error("SSND offset extends past the end of the chunk"));
+    }
+
+    let frames_len = u64::from(comm.frames) * u64::from(comm.channels) * sample_len;
+
+    Ok(Aiff {
+        is_aifc,
+        comm,
+        sound: start..(start + frames_len).min(ssnd.end),
+        silence,
+    })
+}
+
+fn read_comm(payload: &[u8], is_aifc: bool) -> error::Result<Comm> {
+    if payload.len() < if is_aifc { COMM_AIFC_LEN } else { COMM_LEN } {
+        return Err(riff::format_error("truncated COMM chunk"));
+    }
+
+    let mut compression = *b"NONE";
+
+    if is_aifc {
+        compression.copy_from_slice(&payload[18..22]);
+    }
+
+    Ok(Comm {
+        channels: ByteOrder::Big.u16(&payload[0..2]),
+        frames: ByteOrder::Big.u32(&payload[2..6]),
+        bits: ByteOrder::Big.u16(&payload[6..8]),
+        rate: extended_to_f64(&payload[8..18]),
+        compression,
+    })
+}
+
+/// Returns the number of bytes per sample and the byte that fills them with silence.
+fn encoding(comm: &Comm) -> error::Result<(u64, u8)> {
+    let sample_len = u64::from(comm.bits).div_ceil(8);
+
+    match (&comm.compression, comm.bits) {
+        // two's complement, where silence is 0 in either byte order
+        (b"NONE", 1..=32) | (b"twos", 1..=32) | (b"sowt", 1..=32) => Ok((sample_len, 0)),
+        (b"in24", _) => Ok((3, 0)),
+        (b"in32", _) => Ok((4, 0)),
+        (b"fl32", _) | (b"FL32", _) => Ok((4, 0)),
+        (b"fl64", _) | (b"FL64", _) => Ok((8, 0)),
+        // offset binary, centered at 0x80 like 8-bit WAV
+        (b"raw ", 8) => Ok((1, 0x80)),
+        _ => Err(Error::unsupported(format!("{}-bit AIFF-C samples with compression type '{}'",
+            comm.bits, String::from_utf8_lossy(&comm.compression)))),
+    }
+}
+
+/// Converts the 80-bit IEEE 754 extended precision number used for the sample rate.
+fn extended_to_f64(bytes: &[u8]) -> f64 {
+    let exponent = i32::from(ByteOrder::Big.u16(&bytes[0..2]) & 0x7FFF);
+    let mantissa = u64::from(ByteOrder::Big.u32(&bytes[2..6])) << 32 | u64::from(ByteOrder::Big.u32(&bytes[6..10]));
+
+    let value = mantissa as f64 * 2f64.powi(exponent - 16383 - 63);
+
+    if bytes[0] & 0x80 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+fn describe(aiff: &Aiff) -> String {
+    let format = if aiff.is_aifc {
+        format!("AIFF-C '{}'", String::from_utf8_lossy(&aiff.comm.compression))
+    } else {
+        "AIFF".to_owned()
+    };
+
+    format!("{} channel {}-bit {} at {} Hz with {} frames",
+        aiff.comm.channels, aiff.comm.bits, format, aiff.comm.rate, aiff.comm.frames)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::{fill_matching, status_code, Mode, ZERO_FILL_UNSUPPORTED};
+    use super::super::test_util::{aiff_vec, fill_file};
+    use std::fs;
+    use std::io::{Cursor, Write};
+
+    /// Returns `original` with only the sound data replaced by `silence`.
+    fn filled_vec(original: &[u8], sound: Range<u64>, silence: u8) -> Vec<u8> {
+        let mut filled = original.to_vec();
+
+        for d in &mut filled[sound.start as usize..sound.end as usize] {
+            *d = silence;
+        }
+
+        filled
+    }
+
+    #[test]
+    pub fn test_read_aiff() {
+        let aiff = read_aiff(&mut Cursor::new(aiff_vec(None, 16, 2))).unwrap();
+
+        assert!(!aiff.is_aifc);
+        assert_eq!(Comm { channels: 2, frames: 3, bits: 16, rate: 44100.0, compression: *b"NONE" }, aiff.comm);
+        assert_eq!(0, aiff.silence);
+        assert_eq!("2 channel 16-bit AIFF at 44100 Hz with 3 frames", describe(&aiff));
+
+        let aifc = read_aiff(&mut Cursor::new(aiff_vec(Some(b"sowt"), 24, 3))).unwrap();
+        let sound_start = aifc.sound.start as usize;
+
+        assert!(aifc.is_aifc);
+        assert_eq!(b"\xAA\xAA", &aiff_vec(Some(b"sowt"), 24, 3)[sound_start - 2..sound_start]);
+        assert_eq!(18, aifc.sound.end - aifc.sound.start);
+        assert_eq!("2 channel 24-bit AIFF-C 'sowt' at 44100 Hz with 3 frames", describe(&aifc));
+    }
+
+    #[test]
+    pub fn test_fill_aiff() {
+        let original = aiff_vec(None, 16, 2);
+        let sound = read_aiff(&mut Cursor::new(&original)).unwrap().sound;
+
+        let (res, filled) = fill_file("test_fill_aiff.aiff", &original, |file_path| fill_aiff(file_path));
+        res.unwrap();
+
+        // the offset and block padding and the other chunks are kept
+        assert_eq!(filled_vec(&original, sound, 0), filled);
+    }
+
+    #[test]
+    pub fn test_fill_aifc() {
+        let options = FillOptions {
+            mode: Mode::InPlace,
+            verify: true,
+            ..FillOptions::default()
+        };
+
+        for &(compression, bits, sample_len, silence) in &[
+            (b"twos", 16, 2, 0),
+            (b"fl32", 32, 4, 0),
+            (b"fl64", 64, 8, 0),
+            (b"raw ", 8, 1, 0x80),
+        ] {
+            let original = aiff_vec(Some(compression), bits, sample_len);
+            let sound = read_aiff(&mut Cursor::new(&original)).unwrap().sound;
+
+            let (res, filled) = fill_file("test_fill_aifc.aifc", &original, |file_path| fill_aiff_with(file_path, &options));
+            res.unwrap();
+
+            assert_eq!(filled_vec(&original, sound, silence), filled);
+        }
+    }
+
+    #[test]
+    pub fn test_fill_aifc_unsupported() {
+        let original = aiff_vec(Some(b"ima4"), 16, 2);
+        let (res, filled) = fill_file("test_fill_aifc_unsupported.aifc", &original, |file_path| fill_aiff(file_path));
+
+        assert_eq!(ZERO_FILL_UNSUPPORTED, status_code(&res.unwrap_err()));
+        assert_eq!(original, filled);
+    }
+
+    #[test]
+    pub fn test_fill_matching_sniffs_aiff() {
+        let file_path = "test_fill_matching_sniffs_aiff.bin";
+        let original = aiff_vec(None, 8, 1);
+        let sound = read_aiff(&mut Cursor::new(&original)).unwrap().sound;
+        File::create(file_path).unwrap().write_all(&original).unwrap();
+
+        fill_matching(file_path).unwrap();
+
+        let mut filled = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut filled).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(filled_vec(&original, sound, 0), filled);
+    }
+}
@@ -0,0 +1,109 @@
+use super::error;
+use super::{fill_matching_with, FillOptions};
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Applies `fill_matching` to every path across `threads` worker threads,
+/// or as many as there are CPUs if `threads` is 0.
+///
+/// The outcomes are returned in the same order as the given paths.
+pub fn fill_batch<I, P>(file_paths: I, threads: usize, options: &FillOptions) -> Vec<(PathBuf, error::Result<()>)>
+    where I: IntoIterator<Item = P>, P: AsRef<Path> {
+
+    fill_batch_with(file_paths, threads, |file_path| fill_matching_with(file_path, options))
+}
+
+/// Same as `fill_batch`, but applies `fill` to every path.
+pub fn fill_batch_with<I, P, F>(file_paths: I, threads: usize, fill: F) -> Vec<(PathBuf, error::Result<()>)>
+    where I: IntoIterator<Item = P>, P: AsRef<Path>, F: Fn(&Path) -> error::Result<()> + Sync {
+
+    let file_paths: Vec<PathBuf> = file_paths.into_iter().map(|p| p.as_ref().to_owned()).collect();
+
+    let threads = match threads {
+        0 => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        n => n,
+    };
+
+    let threads = threads.min(file_paths.len()).max(1);
+
+    // workers take the next path to fill until all are taken
+    let next = AtomicUsize::new(0);
+
+    let mut outcomes: Vec<(usize, error::Result<()>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| scope.spawn(|| {
+                let mut outcomes = Vec::new();
+
+                loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+
+                    match file_paths.get(index) {
+                        Some(file_path) => outcomes.push((index, fill(file_path))),
+                        None => return outcomes,
+                    }
+                }
+            }))
+            .collect();
+
+        workers.into_iter()
+            .flat_map(|worker| worker.join().expect("zero fill worker panicked"))
+            .collect()
+    });
+
+    outcomes.sort_by_key(|&(index, _)| index);
+
+    file_paths.into_iter()
+        .zip(outcomes)
+        .map(|(file_path, (_, res))| (file_path, res))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::{self, File};
+    use std::io::{Read, Write};
+
+    #[test]
+    pub fn test_fill_batch_keeps_order() {
+        let dir = Path::new("test_fill_batch_keeps_order");
+        fs::create_dir_all(dir).unwrap();
+
+        let file_paths: Vec<_> = (0..32)
+            .map(|i| dir.join(if i == 13 { format!("{}.wav", i) } else { format!("{}.bin", i) }))
+            .collect();
+
+        for file_path in &file_paths {
+            File::create(file_path).unwrap().write_all(b"not zero").unwrap();
+        }
+
+        let outcomes = fill_batch(&file_paths, 4, &FillOptions::default());
+
+        let is_all_zeroes = file_paths.iter()
+            .filter(|file_path| file_path.extension().unwrap() == "bin")
+            .all(|file_path| {
+                let mut buf = Vec::new();
+                File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+                buf.into_iter().all(|d| d == 0)
+            });
+
+        fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(file_paths, outcomes.iter().map(|(file_path, _)| file_path.clone()).collect::<Vec<_>>());
+
+        // only the invalid WAV file fails
+        for (i, (_, res)) in outcomes.iter().enumerate() {
+            assert_eq!(i == 13, res.is_err());
+        }
+
+        assert!(is_all_zeroes);
+    }
+
+    #[test]
+    pub fn test_fill_batch_empty() {
+        let file_paths: Vec<PathBuf> = Vec::new();
+        assert!(fill_batch(file_paths, 0, &FillOptions::default()).is_empty());
+    }
+}
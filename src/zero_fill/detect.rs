@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Number of leading bytes needed by `sniff`.
+pub const SNIFF_LEN: usize = 12;
+
+/// cbindgen:rename-all=QualifiedScreamingSnakeCase
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Detection {
+    /// Uses the file content, falling back to the file extension when the content is not recognized.
+    Auto,
+    /// Uses only the file content.
+    Content,
+    /// Uses only the file extension.
+    Extension,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Wav,
+    Rifx,
+    Rf64,
+    Bw64,
+    Aiff,
+    Aifc,
+    Flac,
+    Ogg,
+    Gif,
+    Png,
+    Jpeg,
+    Pdf,
+    Zip,
+}
+
+impl Format {
+    /// Whether the format holds audio, which must be silenced rather than zeroed as a whole.
+    pub fn is_audio(self) -> bool {
+        matches!(self, Format::Wav | Format::Rifx | Format::Rf64 | Format::Bw64 | Format::Aiff | Format::Aifc
+            | Format::Flac | Format::Ogg)
+    }
+}
+
+pub fn sniff(head: &[u8]) -> Option<Format> {
+    let riff_form = |id: &[u8], form: &[u8]| head.len() >= 12 && &head[0..4] == id && &head[8..12] == form;
+
+    if riff_form(b"RIFF", b"WAVE") {
+        Some(Format::Wav)
+    } else if riff_form(b"RIFX", b"WAVE") {
+        Some(Format::Rifx)
+    } else if riff_form(b"RF64", b"WAVE") {
+        Some(Format::Rf64)
+    } else if riff_form(b"BW64", b"WAVE") {
+        Some(Format::Bw64)
+    } else if riff_form(b"FORM", b"AIFF") {
+        Some(Format::Aiff)
+    } else if riff_form(b"FORM", b"AIFC") {
+        Some(Format::Aifc)
+    } else if head.starts_with(b"fLaC") {
+        Some(Format::Flac)
+    } else if head.starts_with(b"OggS") {
+        Some(Format::Ogg)
+    } else if head.starts_with(b"GIF87a") || head.starts_with(b"GIF89a") {
+        Some(Format::Gif)
+    } else if head.starts_with(b"\x89PNG\r\n\x1A\n") {
+        Some(Format::Png)
+    } else if head.starts_with(b"\xFF\xD8\xFF") {
+        Some(Format::Jpeg)
+    } else if head.starts_with(b"%PDF-") {
+        Some(Format::Pdf)
+    } else if head.starts_with(b"PK\x03\x04") {
+        Some(Format::Zip)
+    } else {
+        None
+    }
+}
+
+/// Reads the leading bytes of the file to be passed to `sniff`.
+pub fn read_head<P: AsRef<Path>>(file_path: P) -> io::Result<Vec<u8>> {
+    read_head_from(File::open(file_path)?)
+}
+
+/// Reads the leading bytes needed by `sniff` from the current position of the reader.
+pub fn read_head_from<R: Read>(reader: R) -> io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    reader.take(SNIFF_LEN as u64).read_to_end(&mut head)?;
+    Ok(head)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_sniff() {
+        assert_eq!(Some(Format::Wav), sniff(b"RIFF\x24\x00\x00\x00WAVEfmt "));
+        assert_eq!(Some(Format::Rifx), sniff(b"RIFX\x00\x00\x00\x24WAVE"));
+        assert_eq!(Some(Format::Rf64), sniff(b"RF64\xFF\xFF\xFF\xFFWAVE"));
+        assert_eq!(Some(Format::Bw64), sniff(b"BW64\xFF\xFF\xFF\xFFWAVE"));
+        assert_eq!(Some(Format::Aiff), sniff(b"FORM\x00\x00\x00\x24AIFF"));
+        assert_eq!(Some(Format::Aifc), sniff(b"FORM\x00\x00\x00\x24AIFC"));
+        assert_eq!(Some(Format::Flac), sniff(b"fLaC\x00\x00\x00\x22"));
+        assert_eq!(Some(Format::Ogg), sniff(b"OggS\x00\x02"));
+        assert_eq!(Some(Format::Gif), sniff(b"GIF89a\x64\x00"));
+        assert_eq!(Some(Format::Png), sniff(b"\x89PNG\r\n\x1A\n\x00\x00"));
+        assert_eq!(Some(Format::Jpeg), sniff(b"\xFF\xD8\xFF\xE0"));
+        assert_eq!(Some(Format::Pdf), sniff(b"%PDF-1.7"));
+        assert_eq!(Some(Format::Zip), sniff(b"PK\x03\x04"));
+    }
+
+    #[test]
+    pub fn test_sniff_unknown() {
+        assert_eq!(None, sniff(b""));
+        assert_eq!(None, sniff(b"RIFF"));
+        assert_eq!(None, sniff(b"RIFF\x24\x00\x00\x00AVI "));
+        assert_eq!(None, sniff(b"plain text"));
+    }
+
+    #[test]
+    pub fn test_is_audio() {
+        assert!(Format::Rifx.is_audio());
+        assert!(Format::Ogg.is_audio());
+        assert!(!Format::Gif.is_audio());
+        assert!(!Format::Zip.is_audio());
+    }
+}
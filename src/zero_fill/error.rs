@@ -1,38 +1,236 @@
+use super::verify::Verification;
 use hound;
 use std;
 use std::fmt::Formatter;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::str::Utf8Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The step of zero filling that failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Open,
+    ReadHeader,
+    Read,
+    Write,
+    /// Flushing, syncing or renaming the filled file into place.
+    Finalize,
+    ListDir,
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str(match *self {
+            Operation::Open => "open",
+            Operation::ReadHeader => "read the header of",
+            Operation::Read => "read",
+            Operation::Write => "write",
+            Operation::Finalize => "finalize",
+            Operation::ListDir => "list",
+        })
+    }
+}
+
 #[derive(Debug)]
-pub enum Error {
+pub enum ErrorKind {
+    /// A C caller passed a NULL pointer where a path or an array was expected.
+    NullPointer,
     CStrConv(Utf8Error),
     Filename,
-    IO(std::io::Error),
-    Wav(hound::Error),
+    IO(io::Error),
+    /// The file is malformed.
+    Format(String),
+    /// The file is well formed, but uses a format or encoding that cannot be zero filled.
+    Unsupported(String),
+    Verify(Verification),
+    /// A caller passed an argument outside of its valid values, such as a time range that ends before it starts.
+    InvalidArgument(String),
+}
+
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    path: Option<PathBuf>,
+    operation: Option<Operation>,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            path: None,
+            operation: None,
+        }
+    }
+
+    pub fn format<S: Into<String>>(msg: S) -> Error {
+        Error::new(ErrorKind::Format(msg.into()))
+    }
+
+    pub fn unsupported<S: Into<String>>(msg: S) -> Error {
+        Error::new(ErrorKind::Unsupported(msg.into()))
+    }
+
+    pub fn invalid_argument<S: Into<String>>(msg: S) -> Error {
+        Error::new(ErrorKind::InvalidArgument(msg.into()))
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The file the error occurred on.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn operation(&self) -> Option<Operation> {
+        self.operation
+    }
+
+    pub fn is_io(&self) -> bool {
+        matches!(self.kind, ErrorKind::IO(_))
+    }
+
+    /// Sets the path, unless the error already carries a more specific one.
+    pub fn with_path<P: AsRef<Path>>(mut self, path: P) -> Error {
+        if self.path.is_none() {
+            self.path = Some(path.as_ref().to_owned());
+        }
+
+        self
+    }
+
+    /// Sets the operation, unless the error already carries a more specific one.
+    pub fn with_operation(mut self, operation: Operation) -> Error {
+        if self.operation.is_none() {
+            self.operation = Some(operation);
+        }
+
+        self
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match *self {
+            ErrorKind::NullPointer => write!(f, "path or array is a NULL pointer"),
+            ErrorKind::CStrConv(ref e) => write!(f, "path is not valid UTF-8: {}", e),
+            ErrorKind::Filename => write!(f, "invalid file name"),
+            ErrorKind::IO(ref e) => write!(f, "{}", e),
+            ErrorKind::Format(ref msg) => write!(f, "malformed file: {}", msg),
+            ErrorKind::Unsupported(ref msg) => write!(f, "unsupported format: {}", msg),
+            ErrorKind::Verify(ref v) => write!(f, "verification failed: {}", v),
+            ErrorKind::InvalidArgument(ref msg) => write!(f, "invalid argument: {}", msg),
+        }
+    }
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match (self.operation, &self.path) {
+            (Some(operation), Some(path)) => write!(f, "cannot {} {}: {}", operation, path.display(), self.kind),
+            (None, Some(path)) => write!(f, "{}: {}", path.display(), self.kind),
+            (Some(operation), None) => write!(f, "cannot {}: {}", operation, self.kind),
+            (None, None) => write!(f, "{}", self.kind),
+        }
     }
 }
 
 impl std::error::Error for Error {
-    fn description(&self) -> &str {
-        "zero_fill::Error: {}"
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.kind {
+            ErrorKind::CStrConv(ref e) => Some(e),
+            ErrorKind::IO(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Error {
+        Error::new(kind)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::new(ErrorKind::IO(e))
     }
 }
 
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Error {
-        Error::IO(e)
+impl From<Utf8Error> for Error {
+    fn from(e: Utf8Error) -> Error {
+        Error::new(ErrorKind::CStrConv(e))
     }
 }
 
 impl From<hound::Error> for Error {
     fn from(e: hound::Error) -> Error {
-        Error::Wav(e)
+        match e {
+            hound::Error::IoError(e) => Error::from(e),
+            hound::Error::FormatError(msg) => Error::format(msg),
+            hound::Error::Unsupported => Error::unsupported("WAV encoding"),
+            e => Error::format(e.to_string()),
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Attaches context to the error of a result.
+pub trait ResultExt<T> {
+    fn during(self, operation: Operation) -> Result<T>;
+    fn for_path<P: AsRef<Path>>(self, path: P) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    fn during(self, operation: Operation) -> Result<T> {
+        self.map_err(|e| e.into().with_operation(operation))
+    }
+
+    fn for_path<P: AsRef<Path>>(self, path: P) -> Result<T> {
+        self.map_err(|e| e.into().with_path(path))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error as StdError;
+
+    #[test]
+    pub fn test_error_context() {
+        let res: std::result::Result<(), io::Error> = Err(io::Error::new(io::ErrorKind::NotFound, "gone"));
+
+        let e = res.during(Operation::Open)
+            .during(Operation::Write)
+            .for_path("a.wav")
+            .unwrap_err();
+
+        assert_eq!(Some(Path::new("a.wav")), e.path());
+        assert_eq!(Some(Operation::Open), e.operation());
+        assert!(e.is_io());
+        assert_eq!("cannot open a.wav: gone", e.to_string());
+        assert_eq!("gone", e.source().unwrap().to_string());
+    }
+
+    #[test]
+    pub fn test_hound_errors() {
+        assert!(Error::from(hound::Error::IoError(io::Error::other("disk"))).is_io());
+
+        match *Error::from(hound::Error::Unsupported).kind() {
+            ErrorKind::Unsupported(_) => (),
+            ref other => panic!("expected an unsupported format, got {:?}", other),
+        }
+
+        match *Error::from(hound::Error::FormatError("no RIFF tag found")).kind() {
+            ErrorKind::Format(ref msg) => assert_eq!("no RIFF tag found", msg),
+            ref other => panic!("expected a malformed file, got {:?}", other),
+        }
+    }
+}
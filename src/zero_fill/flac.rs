@@ -0,0 +1,872 @@
+use super::error::{self, Error, Operation, ResultExt};
+use super::plan::{Plan, Region};
+use super::registry::FlacFiller;
+use super::riff::format_error;
+use super::verify::{self, Mismatch, Verification};
+use super::{replace, write_repeated, FillOptions, Mode};
+use md5;
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::Path;
+
+const MARKER: &[u8; 4] = b"fLaC";
+const BLOCK_HEADER_LEN: usize = 4;
+const STREAMINFO: u8 = 0;
+const SEEKTABLE: u8 = 3;
+const STREAMINFO_LEN: usize = 34;
+const SEEK_POINT_LEN: usize = 18;
+const PLACEHOLDER: u64 = u64::MAX;
+/// Used when STREAMINFO does not give a usable block size.
+const DEFAULT_BLOCK_SIZE: u16 = 4096;
+const READ_BUFFER_LEN: usize = 64 * 1024;
+/// The smallest possible frame: a 6-byte header, a 2-byte CONSTANT subframe and the CRC-16.
+const MIN_FRAME_LEN: u64 = 10;
+/// The first frame numbers that take another byte in the UTF-8 like coding of frame headers.
+const UTF8_LEN_STEPS: [u64; 6] = [0x80, 0x800, 0x1_0000, 0x20_0000, 0x400_0000, 0x8000_0000];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct StreamInfo {
+    min_block: u16,
+    max_block: u16,
+    min_frame: u32,
+    max_frame: u32,
+    sample_rate: u32,
+    channels: u8,
+    bits: u8,
+    /// 0 when unknown.
+    samples: u64,
+    md5: [u8; 16],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Block {
+    kind: u8,
+    data: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+struct Flac {
+    info: StreamInfo,
+    /// Every metadata block, including STREAMINFO, in file order.
+    blocks: Vec<Block>,
+    /// Byte range of the audio frames, which run to the end of the file.
+    frames: Range<u64>,
+}
+
+/// The silent audio that replaces the frames of a stream.
+struct Silence {
+    info: StreamInfo,
+    block_size: u16,
+}
+
+pub fn fill_flac<P: AsRef<Path>>(file_path: P) -> error::Result<()> {
+    fill_flac_with(file_path, &FillOptions::default())
+}
+
+/// Replaces the audio of a FLAC file with silence, keeping the sample rate, channels, bit depth,
+/// sample count and every metadata block, with STREAMINFO and SEEKTABLE updated to match.
+pub fn fill_flac_with<P: AsRef<Path>>(file_path: P, options: &FillOptions) -> error::Result<()> {
+    verify::fill_verified(&FlacFiller, file_path.as_ref(), options)
+}
+
+pub fn fill_flac_unverified(file_path: &Path, options: &FillOptions) -> error::Result<()> {
+    let flac = {
+        let mut file = BufReader::new(File::open(file_path).during(Operation::Open)?);
+        read_flac(&mut file).during(Operation::ReadHeader)?
+    };
+
+    let silence = Silence::new(&flac.info);
+
+    match options.mode {
+        Mode::Rewrite => replace::replace_with(file_path, options.atomic, |writer| {
+            write_flac(writer, &flac, &silence, options.buffer_size)
+        }),
+
+        Mode::InPlace => {
+            let mut file = OpenOptions::new().write(true).open(file_path).during(Operation::Open)?;
+            let len = file.metadata().during(Operation::Open)?.len();
+
+            let new_len = {
+                let mut writer = CountingWriter { inner: BufWriter::new(&mut file), count: 0 };
+                write_flac(&mut writer, &flac, &silence, options.buffer_size).during(Operation::Write)?;
+                writer.flush().during(Operation::Write)?;
+                writer.count
+            };
+
+            // the silence is usually shorter, so the rest of the original audio is zeroed before it is cut off
+            if new_len < len {
+                write_repeated(&mut file, 0, len - new_len, options.buffer_size).during(Operation::Write)?;
+                file.set_len(new_len).during(Operation::Finalize)?;
+            }
+
+            file.sync_all().during(Operation::Finalize)
+        },
+    }
+}
+
+/// Plans the fill, where the region is the audio frames that are replaced by silent frames,
+/// rather than filled with its fill byte.
+pub fn plan_flac<P: AsRef<Path>>(file_path: P) -> error::Result<Plan> {
+    let file_path = file_path.as_ref();
+
+    let plan = || -> error::Result<Plan> {
+        let mut file = BufReader::new(File::open(file_path).during(Operation::Open)?);
+        let flac = read_flac(&mut file).during(Operation::ReadHeader)?;
+        let silence = Silence::new(&flac.info);
+
+        Ok(Plan {
+            filler: "flac".to_owned(),
+            regions: vec![Region { range: flac.frames.clone(), fill: 0 }],
+            len: flac.frames.start + silence.len(),
+            structure: Some(describe(&flac.info)),
+        })
+    };
+
+    plan().for_path(file_path)
+}
+
+/// Verifies the filled FLAC file against a plan made before filling, requiring the stream parameters
+/// to be unchanged, the frames to be exactly the silent frames and the MD5 to match them.
+pub fn verify_flac<P: AsRef<Path>>(file_path: P, plan: &Plan) -> error::Result<Verification> {
+    let file_path = file_path.as_ref();
+    read_silence(file_path, plan).during(Operation::Read).for_path(file_path)
+}
+
+fn read_silence(file_path: &Path, plan: &Plan) -> error::Result<Verification> {
+    let mut file = BufReader::new(File::open(file_path).during(Operation::Open)?);
+    let len = file.get_ref().metadata()?.len();
+
+    let mut verification = Verification::default();
+
+    if len != plan.len {
+        verification.mismatches.push(Mismatch::Len { expected: plan.len, actual: len });
+    }
+
+    let parsed = read_flac(&mut file).map(|flac| (Silence::new(&flac.info), flac));
+
+    let (silence, flac) = match parsed {
+        Ok(parsed) => parsed,
+
+        Err(e) => {
+            if e.is_io() {
+                return Err(e);
+            }
+
+            verification.mismatches.push(Mismatch::Structure {
+                expected: plan.structure.clone().unwrap_or_default(),
+                actual: e.kind().to_string(),
+            });
+
+            return Ok(verification);
+        },
+    };
+
+    let structure = describe(&flac.info);
+
+    if plan.structure.as_ref() != Some(&structure) {
+        verification.mismatches.push(Mismatch::Structure {
+            expected: plan.structure.clone().unwrap_or_default(),
+            actual: structure,
+        });
+    }
+
+    if flac.info.md5 != silence.md5(READ_BUFFER_LEN) {
+        verification.mismatches.push(Mismatch::Structure {
+            expected: "the MD5 of silence in STREAMINFO".to_owned(),
+            actual: "another MD5".to_owned(),
+        });
+    }
+
+    // the frames are compared with freshly encoded silence, so any leftover audio counts as not filled
+    let mut offset = flac.frames.start;
+    let mut first_offset = None;
+    let mut count = 0;
+
+    file.seek(SeekFrom::Start(offset))?;
+
+    for index in 0..silence.frame_count() {
+        let expected = silence.frame(index);
+        let mut actual = Vec::with_capacity(expected.len());
+        (&mut file).take(expected.len() as u64).read_to_end(&mut actual)?;
+
+        for (i, (&a, &b)) in expected.iter().zip(&actual).enumerate() {
+            if a != b {
+                first_offset = first_offset.or(Some(offset + i as u64));
+                count += 1;
+            }
+        }
+
+        if actual.len() < expected.len() {
+            first_offset = first_offset.or(Some(offset + actual.len() as u64));
+            count += (expected.len() - actual.len()) as u64;
+        }
+
+        offset += actual.len() as u64;
+    }
+
+    if offset < flac.frames.end {
+        first_offset = first_offset.or(Some(offset));
+        count += flac.frames.end - offset;
+    }
+
+    if let Some(first_offset) = first_offset {
+        verification.mismatches.push(Mismatch::NotFilled { range: flac.frames, fill: 0, first_offset, count });
+    }
+
+    Ok(verification)
+}
+
+fn read_flac<R: Read + Seek>(reader: &mut R) -> error::Result<Flac> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut marker = [0u8; 4];
+    read_exact_or_format(reader, &mut marker, "not a FLAC file")?;
+
+    if &marker != MARKER {
+        return Err(format_error("not a FLAC file"));
+    }
+
+    let mut blocks = Vec::new();
+    let mut offset = MARKER.len() as u64;
+
+    loop {
+        let mut header = [0u8; BLOCK_HEADER_LEN];
+        read_exact_or_format(reader, &mut header, "truncated metadata block header")?;
+
+        let len = u32::from(header[1]) << 16 | u32::from(header[2]) << 8 | u32::from(header[3]);
+        offset += (BLOCK_HEADER_LEN as u64) + u64::from(len);
+
+        if offset > file_len {
+            return Err(format_error("metadata block extends past the end of the file"));
+        }
+
+        let mut data = vec![0u8; len as usize];
+        read_exact_or_format(reader, &mut data, "truncated metadata block")?;
+        blocks.push(Block { kind: header[0] & 0x7F, data });
+
+        // the high bit marks the last metadata block
+        if header[0] & 0x80 != 0 {
+            break;
+        }
+    }
+
+    let info = match blocks.first() {
+        Some(block) if block.kind == STREAMINFO && block.data.len() == STREAMINFO_LEN => parse_streaminfo(&block.data),
+        _ => return Err(format_error("missing STREAMINFO block")),
+    };
+
+    if info.sample_rate == 0 || info.bits < 4 {
+        return Err(format_error("invalid STREAMINFO block"));
+    }
+
+    if info.samples == 0 && offset < file_len {
+        return Err(Error::unsupported("FLAC stream with an unknown number of samples"));
+    }
+
+    // every frame holds at most `max_block` samples, which bounds the work of encoding the silence by the file size
+    let max_block = if info.max_block >= 16 { u64::from(info.max_block) } else { u64::from(u16::MAX) };
+
+    if info.samples > (file_len - offset).div_ceil(MIN_FRAME_LEN) * max_block {
+        return Err(format_error("STREAMINFO sample count does not fit in the frames"));
+    }
+
+    Ok(Flac {
+        info,
+        blocks,
+        frames: offset..file_len,
+    })
+}
+
+fn read_exact_or_format<R: Read>(reader: &mut R, buf: &mut [u8], msg: &'static str) -> error::Result<()> {
+    match reader.read_exact(buf) {
+        Ok(_) => Ok(()),
+        Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => Err(format_error(msg)),
+        Err(e) => Err(Error::from(e)),
+    }
+}
+
+fn parse_streaminfo(data: &[u8]) -> StreamInfo {
+    // sample rate, channels, bits per sample and sample count are packed into 20, 3, 5 and 36 bits
+    let packed = be(&data[10..18]);
+
+    let mut md5 = [0u8; 16];
+    md5.copy_from_slice(&data[18..34]);
+
+    StreamInfo {
+        min_block: be(&data[0..2]) as u16,
+        max_block: be(&data[2..4]) as u16,
+        min_frame: be(&data[4..7]) as u32,
+        max_frame: be(&data[7..10]) as u32,
+        sample_rate: (packed >> 44) as u32,
+        channels: ((packed >> 41) & 0x07) as u8 + 1,
+        bits: ((packed >> 36) & 0x1F) as u8 + 1,
+        samples: packed & 0x0F_FFFF_FFFF,
+        md5,
+    }
+}
+
+fn be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |n, &b| n << 8 | u64::from(b))
+}
+
+fn encode_streaminfo(info: &StreamInfo) -> Vec<u8> {
+    let packed = u64::from(info.sample_rate) << 44
+        | u64::from(info.channels - 1) << 41
+        | u64::from(info.bits - 1) << 36
+        | info.samples;
+
+    let mut data = Vec::with_capacity(STREAMINFO_LEN);
+    data.extend_from_slice(&info.min_block.to_be_bytes());
+    data.extend_from_slice(&info.max_block.to_be_bytes());
+    data.extend_from_slice(&info.min_frame.to_be_bytes()[1..]);
+    data.extend_from_slice(&info.max_frame.to_be_bytes()[1..]);
+    data.extend_from_slice(&packed.to_be_bytes());
+    data.extend_from_slice(&info.md5);
+    data
+}
+
+impl Silence {
+    fn new(info: &StreamInfo) -> Silence {
+        let block_size = if info.max_block >= 16 { info.max_block } else { DEFAULT_BLOCK_SIZE };
+
+        Silence {
+            info: info.clone(),
+            block_size,
+        }
+    }
+
+    fn frame_count(&self) -> u64 {
+        self.info.samples.div_ceil(u64::from(self.block_size))
+    }
+
+    fn frame_len(&self, index: u64) -> u16 {
+        (self.info.samples - index * u64::from(self.block_size)).min(u64::from(self.block_size)) as u16
+    }
+
+    /// Total length of the silent frames.
+    fn len(&self) -> u64 {
+        self.offset(self.frame_count())
+    }
+
+    /// Length of the encoded frame, which only depends on the length of its frame number and block size.
+    fn encoded_len(&self, index: u64) -> u64 {
+        let block_len = if self.frame_len(index) <= 256 { 1 } else { 2 };
+        self.fixed_len() + utf8_len(index) + block_len
+    }
+
+    /// Length of a frame without its frame number and block size.
+    fn fixed_len(&self) -> u64 {
+        let subframe_bits = u64::from(self.info.channels) * (8 + u64::from(self.info.bits));
+        // sync code and codes, CRC-8, subframes and CRC-16
+        4 + 1 + subframe_bits.div_ceil(8) + 2
+    }
+
+    /// Byte offset of the frame relative to the first frame, or the total length for `frame_count`.
+    fn offset(&self, index: u64) -> u64 {
+        // every frame but the last holds a whole block, so only their frame numbers differ in length
+        let whole = index.min(self.frame_count().saturating_sub(1));
+        let block_len = if self.block_size <= 256 { 1 } else { 2 };
+
+        // each frame number takes a byte, plus one for every step it reaches
+        let numbers_len = whole + UTF8_LEN_STEPS.iter().map(|&step| whole.saturating_sub(step)).sum::<u64>();
+        let offset = whole * (self.fixed_len() + block_len) + numbers_len;
+
+        if index > whole {
+            offset + self.encoded_len(whole)
+        } else {
+            offset
+        }
+    }
+
+    /// Encodes a frame holding a CONSTANT subframe of 0 for every channel.
+    fn frame(&self, index: u64) -> Vec<u8> {
+        let mut frame = frame_header(&self.info, index, self.frame_len(index));
+
+        // each subframe is an 8-bit header of 0, which means CONSTANT, followed by a 0 sample,
+        // and the subframes are padded to a whole byte
+        let subframe_bits = u64::from(self.info.channels) * (8 + u64::from(self.info.bits));
+        frame.resize(frame.len() + subframe_bits.div_ceil(8) as usize, 0);
+
+        let crc = crc16(&frame);
+        frame.extend_from_slice(&crc.to_be_bytes());
+        frame
+    }
+
+    /// The STREAMINFO block of the silent stream.
+    fn streaminfo(&self, buffer_len: usize) -> StreamInfo {
+        // whole frames only grow with their frame number, so only the first and the last two can be extremes
+        let count = self.frame_count();
+        let frame_lens: Vec<_> = [0, count.saturating_sub(2), count.saturating_sub(1)].iter()
+            .filter(|&&index| index < count)
+            .map(|&index| self.encoded_len(index) as u32)
+            .collect();
+
+        StreamInfo {
+            min_block: self.block_size,
+            max_block: self.block_size,
+            min_frame: frame_lens.iter().cloned().min().unwrap_or(0),
+            max_frame: frame_lens.iter().cloned().max().unwrap_or(0),
+            md5: self.md5(buffer_len),
+            ..self.info.clone()
+        }
+    }
+
+    /// The MD5 of the decoded samples, which are all 0.
+    fn md5(&self, buffer_len: usize) -> [u8; 16] {
+        let sample_len = u64::from(self.info.bits).div_ceil(8);
+        let mut remaining = self.info.samples * u64::from(self.info.channels) * sample_len;
+
+        let buf = vec![0u8; (buffer_len.max(1) as u64).min(remaining) as usize];
+        let mut context = md5::Context::new();
+
+        while remaining > 0 {
+            let n = remaining.min(buf.len() as u64) as usize;
+            context.consume(&buf[..n]);
+            remaining -= n as u64;
+        }
+
+        context.compute().0
+    }
+
+    /// Points every seek point at the silent frame holding its sample, keeping the number of points.
+    fn seektable(&self, data: &[u8]) -> Vec<u8> {
+        let block_size = u64::from(self.block_size);
+        let mut indices: Vec<u64> = data.chunks_exact(SEEK_POINT_LEN)
+            .map(|point| be(&point[0..8]))
+            .filter(|&sample| sample != PLACEHOLDER && sample < self.info.samples)
+            .map(|sample| sample / block_size)
+            .collect();
+
+        // seek points must stay sorted and unique, with placeholders at the end
+        indices.sort();
+        indices.dedup();
+
+        let mut seektable = Vec::with_capacity(data.len());
+
+        for &index in &indices {
+            seektable.extend_from_slice(&(index * block_size).to_be_bytes());
+            seektable.extend_from_slice(&self.offset(index).to_be_bytes());
+            seektable.extend_from_slice(&self.frame_len(index).to_be_bytes());
+        }
+
+        while seektable.len() + SEEK_POINT_LEN <= data.len() {
+            seektable.extend_from_slice(&PLACEHOLDER.to_be_bytes());
+            seektable.extend_from_slice(&[0; SEEK_POINT_LEN - 8]);
+        }
+
+        seektable
+    }
+}
+
+fn frame_header(info: &StreamInfo, index: u64, block_len: u16) -> Vec<u8> {
+    // the codes that mean "as in STREAMINFO" are used when there is no dedicated code
+    let rate_code = match info.sample_rate {
+        88_200 => 0b0001,
+        176_400 => 0b0010,
+        192_000 => 0b0011,
+        8_000 => 0b0100,
+        16_000 => 0b0101,
+        22_050 => 0b0110,
+        24_000 => 0b0111,
+        32_000 => 0b1000,
+        44_100 => 0b1001,
+        48_000 => 0b1010,
+        96_000 => 0b1011,
+        _ => 0b0000,
+    };
+
+    let bits_code = match info.bits {
+        8 => 0b001,
+        12 => 0b010,
+        16 => 0b100,
+        20 => 0b101,
+        24 => 0b110,
+        _ => 0b000,
+    };
+
+    // the block size follows the frame number as 8 or 16 bits, minus one
+    let block_code = if block_len <= 256 { 0b0110 } else { 0b0111 };
+
+    // sync code with the fixed block size strategy, then independently coded channels
+    let mut header = vec![0xFF, 0xF8, block_code << 4 | rate_code, (info.channels - 1) << 4 | bits_code << 1];
+    push_utf8(&mut header, index);
+
+    if block_len <= 256 {
+        header.push((block_len - 1) as u8);
+    } else {
+        header.extend_from_slice(&(block_len - 1).to_be_bytes());
+    }
+
+    let crc = crc8(&header);
+    header.push(crc);
+    header
+}
+
+/// Number of bytes `push_utf8` takes for the frame number.
+fn utf8_len(n: u64) -> u64 {
+    1 + UTF8_LEN_STEPS.iter().filter(|&&step| n >= step).count() as u64
+}
+
+/// Appends the frame number in the UTF-8 like coding of FLAC frame headers.
+fn push_utf8(out: &mut Vec<u8>, n: u64) {
+    if n < 0x80 {
+        return out.push(n as u8);
+    }
+
+    let extra = match n {
+        0..=0x7FF => 1,
+        0x800..=0xFFFF => 2,
+        0x1_0000..=0x1F_FFFF => 3,
+        0x20_0000..=0x3FF_FFFF => 4,
+        0x400_0000..=0x7FFF_FFFF => 5,
+        _ => 6,
+    };
+
+    let lead = (0xFF00u16 >> (extra + 1)) as u8;
+    out.push(lead | (n >> (6 * extra)) as u8);
+
+    for i in (0..extra).rev() {
+        out.push(0x80 | ((n >> (6 * i)) & 0x3F) as u8);
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |crc, &b| {
+        (0..8).fold(crc ^ b, |crc, _| if crc & 0x80 != 0 { crc << 1 ^ 0x07 } else { crc << 1 })
+    })
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |crc, &b| {
+        (0..8).fold(crc ^ u16::from(b) << 8, |crc, _| if crc & 0x8000 != 0 { crc << 1 ^ 0x8005 } else { crc << 1 })
+    })
+}
+
+fn write_flac<W: Write>(writer: &mut W, flac: &Flac, silence: &Silence, buffer_size: usize) -> error::Result<()> {
+    writer.write_all(MARKER)?;
+
+    for (i, block) in flac.blocks.iter().enumerate() {
+        let data = match block.kind {
+            STREAMINFO => encode_streaminfo(&silence.streaminfo(buffer_size)),
+            SEEKTABLE => silence.seektable(&block.data),
+            _ => block.data.clone(),
+        };
+
+        let last = if i + 1 == flac.blocks.len() { 0x80 } else { 0 };
+        writer.write_all(&[last | block.kind])?;
+        writer.write_all(&(data.len() as u32).to_be_bytes()[1..])?;
+        writer.write_all(&data)?;
+    }
+
+    for index in 0..silence.frame_count() {
+        writer.write_all(&silence.frame(index))?;
+    }
+
+    Ok(())
+}
+
+struct CountingWriter<W> {
+    inner: W,
+    count: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn describe(info: &StreamInfo) -> String {
+    format!("{} channel {}-bit FLAC at {} Hz with {} samples", info.channels, info.bits, info.sample_rate, info.samples)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::{fill_matching, status_code, ZERO_FILL_FORMAT, ZERO_FILL_UNSUPPORTED};
+    use super::super::test_util::fill_file;
+    use claxon::FlacReader;
+    use std::fs;
+    use std::io::Cursor;
+
+    const VORBIS_COMMENT: u8 = 4;
+    const PICTURE: u8 = 6;
+
+    fn sample(i: usize, channel: usize, bits: u8) -> i32 {
+        let half = 1i32 << (bits - 1);
+        ((i * 7919 + channel * 104_729) as i32 % (2 * half)) - half
+    }
+
+    fn vorbis_comment() -> Vec<u8> {
+        let mut data = Vec::new();
+
+        for s in &["zero_fill test", "TITLE=zero"] {
+            if *s == "TITLE=zero" {
+                data.extend_from_slice(&1u32.to_le_bytes());
+            }
+
+            data.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            data.extend_from_slice(s.as_bytes());
+        }
+
+        data
+    }
+
+    /// A FLAC file with VERBATIM subframes, a SEEKTABLE with a point in every frame and a placeholder,
+    /// a VORBIS_COMMENT, a PICTURE and PADDING.
+    fn flac_vec(channels: u8, bits: u8, sample_rate: u32, samples: u64, block_size: u16) -> Vec<u8> {
+        let mut context = md5::Context::new();
+
+        for i in 0..samples as usize {
+            for channel in 0..channels as usize {
+                context.consume(&sample(i, channel, bits).to_le_bytes()[..bits as usize / 8]);
+            }
+        }
+
+        let info = StreamInfo {
+            min_block: block_size,
+            max_block: block_size,
+            min_frame: 0,
+            max_frame: 0,
+            sample_rate,
+            channels,
+            bits,
+            samples,
+            md5: context.compute().0,
+        };
+
+        let mut frames = Vec::new();
+        let mut seektable = Vec::new();
+
+        for (index, start) in (0..samples).step_by(block_size as usize).enumerate() {
+            let len = (samples - start).min(u64::from(block_size)) as u16;
+
+            seektable.extend_from_slice(&start.to_be_bytes());
+            seektable.extend_from_slice(&(frames.len() as u64).to_be_bytes());
+            seektable.extend_from_slice(&len.to_be_bytes());
+
+            let mut frame = frame_header(&info, index as u64, len);
+
+            for channel in 0..channels as usize {
+                // a VERBATIM subframe header
+                frame.push(0x02);
+
+                for i in start as usize..start as usize + len as usize {
+                    frame.extend_from_slice(&sample(i, channel, bits).to_be_bytes()[4 - bits as usize / 8..]);
+                }
+            }
+
+            let crc = crc16(&frame);
+            frame.extend_from_slice(&crc.to_be_bytes());
+            frames.extend(frame);
+        }
+
+        seektable.extend_from_slice(&PLACEHOLDER.to_be_bytes());
+        seektable.extend_from_slice(&[0xAB; SEEK_POINT_LEN - 8]);
+
+        let blocks = [
+            Block { kind: STREAMINFO, data: encode_streaminfo(&info) },
+            Block { kind: SEEKTABLE, data: seektable },
+            Block { kind: VORBIS_COMMENT, data: vorbis_comment() },
+            Block { kind: PICTURE, data: b"not really a picture".to_vec() },
+            Block { kind: 1, data: vec![0; 16] },
+        ];
+
+        let mut flac = MARKER.to_vec();
+
+        for (i, block) in blocks.iter().enumerate() {
+            let last = if i + 1 == blocks.len() { 0x80 } else { 0 };
+            flac.push(last | block.kind);
+            flac.extend_from_slice(&(block.data.len() as u32).to_be_bytes()[1..]);
+            flac.extend_from_slice(&block.data);
+        }
+
+        flac.extend(frames);
+        flac
+    }
+
+    /// Decodes the file with an independent decoder, returning its STREAMINFO, title and samples.
+    fn decode(data: &[u8]) -> (claxon::metadata::StreamInfo, Option<String>, Vec<i32>) {
+        let mut reader = FlacReader::new(Cursor::new(data)).unwrap();
+        let title = reader.get_tag("TITLE").next().map(|title| title.to_owned());
+        let samples = reader.samples().collect::<Result<Vec<_>, _>>().unwrap();
+
+        (reader.streaminfo(), title, samples)
+    }
+
+    #[test]
+    pub fn test_read_flac() {
+        let original = flac_vec(2, 16, 44100, 10000, 4096);
+        let flac = read_flac(&mut Cursor::new(&original)).unwrap();
+
+        assert_eq!(5, flac.blocks.len());
+        assert_eq!(original.len() as u64, flac.frames.end);
+        assert_eq!(b"\xFF\xF8", &original[flac.frames.start as usize..flac.frames.start as usize + 2]);
+        assert_eq!("2 channel 16-bit FLAC at 44100 Hz with 10000 samples", describe(&flac.info));
+
+        // the fixture itself must decode, or the tests below prove nothing
+        let (info, title, samples) = decode(&original);
+
+        assert_eq!(10000, info.samples.unwrap());
+        assert_eq!(Some("zero".to_owned()), title);
+        assert_eq!(sample(9999, 1, 16), *samples.last().unwrap());
+    }
+
+    #[test]
+    pub fn test_fill_flac() {
+        for &(channels, bits, sample_rate, samples, block_size) in &[
+            (2, 16, 44100, 10000, 4096),
+            (1, 24, 11025, 300, 192),
+            (3, 8, 48000, 17, 16),
+        ] {
+            let original = flac_vec(channels, bits, sample_rate, samples, block_size);
+            let (res, filled) = fill_file("test_fill_flac.flac", &original, |file_path| fill_flac(file_path));
+            res.unwrap();
+
+            let (info, title, decoded) = decode(&filled);
+
+            assert_eq!((sample_rate, u32::from(channels), u32::from(bits), Some(samples)),
+                (info.sample_rate, info.channels, info.bits_per_sample, info.samples));
+            assert_eq!(Some("zero".to_owned()), title);
+            assert_eq!(samples * u64::from(channels), decoded.len() as u64);
+            assert!(decoded.iter().all(|&s| s == 0));
+
+            // the MD5 is that of the decoded samples, as little-endian bytes
+            let mut context = md5::Context::new();
+
+            for s in &decoded {
+                context.consume(&s.to_le_bytes()[..bits as usize / 8]);
+            }
+
+            let flac = read_flac(&mut Cursor::new(&filled)).unwrap();
+            assert_eq!(context.compute().0, flac.info.md5);
+
+            // the other metadata blocks are kept as they were
+            let original_blocks = read_flac(&mut Cursor::new(&original)).unwrap().blocks;
+            assert_eq!(original_blocks[2..], flac.blocks[2..]);
+
+            // every seek point still starts a frame, and the placeholder is kept at the end
+            let seektable = &flac.blocks[1].data;
+            assert_eq!(original_blocks[1].data.len(), seektable.len());
+            assert_eq!(&PLACEHOLDER.to_be_bytes(), &seektable[seektable.len() - SEEK_POINT_LEN..][..8]);
+
+            for point in seektable.chunks_exact(SEEK_POINT_LEN).filter(|point| point[..8] != PLACEHOLDER.to_be_bytes()) {
+                let offset = flac.frames.start as usize + be(&point[8..16]) as usize;
+                assert_eq!(b"\xFF\xF8", &filled[offset..offset + 2]);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_fill_flac_in_place() {
+        let original = flac_vec(2, 16, 44100, 10000, 4096);
+        let (res, rewritten) = fill_file("test_fill_flac_in_place.flac", &original, |file_path| fill_flac(file_path));
+        res.unwrap();
+
+        let options = FillOptions {
+            mode: Mode::InPlace,
+            verify: true,
+            ..FillOptions::default()
+        };
+
+        let (res, filled) = fill_file("test_fill_flac_in_place.flac", &original, |file_path| fill_flac_with(file_path, &options));
+        res.unwrap();
+
+        assert_eq!(rewritten, filled);
+        assert!(filled.len() < original.len());
+    }
+
+    #[test]
+    pub fn test_verify_flac() {
+        let file_path = "test_verify_flac.flac";
+        let original = flac_vec(2, 16, 44100, 10000, 4096);
+        File::create(file_path).unwrap().write_all(&original).unwrap();
+
+        let plan = plan_flac(file_path).unwrap();
+        let unfilled = verify_flac(file_path, &plan).unwrap();
+        fill_flac(file_path).unwrap();
+        let filled = verify_flac(file_path, &plan).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert!(filled.is_ok(), "{}", filled);
+        assert!(unfilled.mismatches.iter().any(|mismatch| matches!(*mismatch, Mismatch::NotFilled { .. })));
+    }
+
+    #[test]
+    pub fn test_fill_flac_unknown_samples() {
+        let mut original = flac_vec(1, 16, 44100, 100, 4096);
+        // clears the low 32 bits of the sample count in STREAMINFO
+        original[8 + 14..8 + 18].copy_from_slice(&[0; 4]);
+
+        let (res, filled) = fill_file("test_fill_flac_unknown_samples.flac", &original, |file_path| fill_flac(file_path));
+
+        assert_eq!(ZERO_FILL_UNSUPPORTED, status_code(&res.unwrap_err()));
+        assert_eq!(original, filled);
+    }
+
+    #[test]
+    pub fn test_fill_flac_impossible_samples() {
+        let mut original = flac_vec(1, 16, 44100, 100, 16);
+        // sets the sample count in STREAMINFO to its maximum, which would take hours to encode as silence
+        original[8 + 13] |= 0x0F;
+        original[8 + 14..8 + 18].copy_from_slice(&[0xFF; 4]);
+
+        let (res, filled) = fill_file("test_fill_flac_impossible_samples.flac", &original, |file_path| fill_flac(file_path));
+
+        assert_eq!(ZERO_FILL_FORMAT, status_code(&res.unwrap_err()));
+        assert_eq!(original, filled);
+    }
+
+    #[test]
+    pub fn test_silence_offsets() {
+        // the frame numbers reach 2-byte codes, and the last frames hold 5 and 300 samples
+        for &(block_size, samples) in &[(16, 16 * 0x900 + 5), (4096, 4096 * 3 + 300), (4096, 0)] {
+            let info = StreamInfo {
+                min_block: block_size,
+                max_block: block_size,
+                min_frame: 0,
+                max_frame: 0,
+                sample_rate: 44100,
+                channels: 2,
+                bits: 24,
+                samples,
+                md5: [0; 16],
+            };
+
+            let silence = Silence::new(&info);
+            let mut offset = 0;
+
+            for index in 0..silence.frame_count() {
+                assert_eq!(offset, silence.offset(index));
+                assert_eq!(silence.frame(index).len() as u64, silence.encoded_len(index));
+                offset += silence.frame(index).len() as u64;
+            }
+
+            assert_eq!(offset, silence.len());
+        }
+    }
+
+    #[test]
+    pub fn test_fill_matching_sniffs_flac() {
+        let file_path = "test_fill_matching_sniffs_flac.bin";
+        let original = flac_vec(1, 16, 44100, 100, 4096);
+        File::create(file_path).unwrap().write_all(&original).unwrap();
+
+        fill_matching(file_path).unwrap();
+
+        let mut filled = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut filled).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert!(decode(&filled).2.iter().all(|&s| s == 0));
+    }
+}
@@ -0,0 +1,39 @@
+use super::error::{Error, ErrorKind};
+
+use std::cell::RefCell;
+
+struct LastError {
+    message: String,
+    os_error: i32,
+}
+
+thread_local! {
+    // kept per thread so that concurrent C callers each see the error of their own call
+    static LAST_ERROR: RefCell<Option<LastError>> = const { RefCell::new(None) };
+}
+
+pub fn set(e: &Error) {
+    let os_error = match *e.kind() {
+        ErrorKind::IO(ref e) => e.raw_os_error().unwrap_or(0),
+        _ => 0,
+    };
+
+    let last = LastError {
+        message: e.to_string(),
+        os_error,
+    };
+
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(last));
+}
+
+pub fn clear() {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+}
+
+pub fn message() -> Option<String> {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ref().map(|last| last.message.clone()))
+}
+
+pub fn os_error() -> i32 {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ref().map_or(0, |last| last.os_error))
+}
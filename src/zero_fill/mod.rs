@@ -1,99 +1,801 @@
+pub mod aiff;
+pub mod batch;
+pub mod detect;
 pub mod error;
+pub mod flac;
+mod last_error;
+pub mod plan;
+pub mod redact;
+pub mod registry;
+mod replace;
+mod riff;
+pub mod verify;
+pub mod walk;
+mod wav;
 
-use self::error::Error;
-use hound::{WavReader, WavWriter};
+#[cfg(test)]
+mod test_util;
+
+use self::detect::{Detection, Format};
+use self::error::{Error, ErrorKind, Operation, ResultExt};
+use self::plan::{Plan, Region};
+use self::redact::TimeRange;
+use self::registry::{AnyFiller, WavFiller};
+use self::verify::Verification;
 
 use std::ffi::CStr;
-use std::fs::{self, File};
-use std::io::Write;
-use std::os::raw::c_char;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::os::raw::{c_char, c_int};
+use std::ops::Range;
 use std::path::Path;
+use std::ptr;
+
+pub const ZERO_FILL_OK: i32 = Status::ZERO_FILL_OK as i32;
+pub const ZERO_FILL_C_STR_CONV: i32 = Status::ZERO_FILL_C_STR_CONV as i32;
+pub const ZERO_FILL_FILENAME: i32 = Status::ZERO_FILL_FILENAME as i32;
+pub const ZERO_FILL_IO: i32 = Status::ZERO_FILL_IO as i32;
+pub const ZERO_FILL_FORMAT: i32 = Status::ZERO_FILL_FORMAT as i32;
+/// Former name of `ZERO_FILL_FORMAT`, from when WAV was the only format with a structure.
+pub const ZERO_FILL_WAV: i32 = ZERO_FILL_FORMAT;
+pub const ZERO_FILL_VERIFY: i32 = Status::ZERO_FILL_VERIFY as i32;
+pub const ZERO_FILL_UNSUPPORTED: i32 = Status::ZERO_FILL_UNSUPPORTED as i32;
+pub const ZERO_FILL_NULL_POINTER: i32 = Status::ZERO_FILL_NULL_POINTER as i32;
+pub const ZERO_FILL_INVALID_ARGUMENT: i32 = Status::ZERO_FILL_INVALID_ARGUMENT as i32;
+
+/// Status codes returned by the C functions.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    ZERO_FILL_OK = 0,
+    /// The path is not valid UTF-8, which is only required outside of Unix.
+    ZERO_FILL_C_STR_CONV = 1,
+    ZERO_FILL_FILENAME = 2,
+    ZERO_FILL_IO = 3,
+    /// The file is malformed.
+    ZERO_FILL_FORMAT = 4,
+    ZERO_FILL_VERIFY = 5,
+    /// The file uses a format or encoding that cannot be zero filled.
+    ZERO_FILL_UNSUPPORTED = 6,
+    /// A path, path array or range array is NULL.
+    ZERO_FILL_NULL_POINTER = 7,
+    /// An argument other than a path is outside of its valid values, such as a time range that ends before it starts.
+    ZERO_FILL_INVALID_ARGUMENT = 8,
+}
+
+const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+/// The most kept bytes a `Mode::Rewrite` fill without `atomic` holds in memory while the original is truncated.
+/// Fills that keep more, such as redactions, are written atomically so that the kept bytes are copied instead.
+const MAX_KEPT_IN_MEMORY: u64 = 1024 * 1024;
+
+/// Zero fills the file using the filler matching its content or extension.
+///
+/// On Unix the path may hold any bytes, elsewhere it must be UTF-8.
+///
+/// # Safety
+///
+/// `file_path` must either be NULL or point to a valid NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_matching(file_path: *const c_char) -> i32 {
+    c_fill_matching(file_path, &FillOptions::default())
+}
+
+/// Same as `zero_fill_matching`, but replaces the file atomically.
+///
+/// # Safety
+///
+/// `file_path` must either be NULL or point to a valid NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_matching_atomic(file_path: *const c_char) -> i32 {
+    let options = FillOptions {
+        atomic: true,
+        ..FillOptions::default()
+    };
+
+    c_fill_matching(file_path, &options)
+}
+
+/// Same as `zero_fill_matching`, but always uses the WAV filler.
+///
+/// # Safety
+///
+/// `file_path` must either be NULL or point to a valid NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_wav(file_path: *const c_char) -> i32 {
+    to_status(c_str_path(file_path).and_then(fill_wav))
+}
+
+/// Same as `zero_fill_matching`, but always zero fills the whole file.
+///
+/// # Safety
+///
+/// `file_path` must either be NULL or point to a valid NUL-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_any(file_path: *const c_char) -> i32 {
+    to_status(c_str_path(file_path).and_then(fill_any))
+}
+
+/// Zero fills the open file behind `fd` in place, using the WAV or AIFF filler if its content is
+/// WAV or AIFF and zeroing all of it otherwise. FLAC content fails with `ZERO_FILL_UNSUPPORTED`,
+/// as it is re-encoded into a shorter file.
+///
+/// The descriptor must be seekable and open for both reading and writing. It is left open,
+/// at an unspecified offset, and nothing is synced to disk. Only available on Unix.
+///
+/// # Safety
+///
+/// `fd` must either be negative or an open file descriptor that is not closed during the call.
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_fd(fd: c_int) -> i32 {
+    use std::mem::ManuallyDrop;
+    use std::os::unix::io::FromRawFd;
+
+    if fd < 0 {
+        return to_status(Err(Error::invalid_argument("negative file descriptor")));
+    }
+
+    // the descriptor belongs to the caller, so it must not be closed when the file is dropped
+    let mut file = ManuallyDrop::new(File::from_raw_fd(fd));
+    to_status(fill_matching_stream(&mut *file, &FillOptions::default()))
+}
+
+/// Options for `zero_fill_with_options`, mirroring `FillOptions`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CFillOptions {
+    pub mode: Mode,
+    pub atomic: bool,
+    /// 0 uses the default buffer size.
+    pub buffer_size: usize,
+    pub detection: Detection,
+    pub verify: bool,
+    pub recovery: Recovery,
+}
+
+impl Default for CFillOptions {
+    fn default() -> CFillOptions {
+        CFillOptions::from(&FillOptions::default())
+    }
+}
+
+impl From<&FillOptions> for CFillOptions {
+    fn from(options: &FillOptions) -> CFillOptions {
+        CFillOptions {
+            mode: options.mode,
+            atomic: options.atomic,
+            buffer_size: options.buffer_size,
+            detection: options.detection,
+            verify: options.verify,
+            recovery: options.recovery,
+        }
+    }
+}
+
+impl From<CFillOptions> for FillOptions {
+    fn from(options: CFillOptions) -> FillOptions {
+        FillOptions {
+            mode: options.mode,
+            atomic: options.atomic,
+            buffer_size: if options.buffer_size == 0 { DEFAULT_BUFFER_SIZE } else { options.buffer_size },
+            detection: options.detection,
+            verify: options.verify,
+            recovery: options.recovery,
+        }
+    }
+}
+
+/// Returns the options that `zero_fill_matching` uses, to be adjusted before calling `zero_fill_with_options`.
+#[no_mangle]
+pub extern "C" fn zero_fill_default_options() -> CFillOptions {
+    CFillOptions::default()
+}
+
+/// Same as `zero_fill_matching`, but with the given options, or the default ones if `options` is NULL.
+///
+/// # Safety
+///
+/// `file_path` must either be NULL or point to a valid NUL-terminated C string, and `options`
+/// must either be NULL or point to options with valid `mode`, `detection` and `recovery` values.
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_with_options(file_path: *const c_char, options: *const CFillOptions) -> i32 {
+    c_fill_matching(file_path, &c_options(options))
+}
+
+/// Same as `zero_fill_wav`, but with the given options, or the default ones if `options` is NULL.
+/// `detection` is ignored, as the WAV filler is always used.
+///
+/// # Safety
+///
+/// `file_path` must either be NULL or point to a valid NUL-terminated C string, and `options`
+/// must either be NULL or point to options with valid `mode`, `detection` and `recovery` values.
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_wav_with_options(file_path: *const c_char, options: *const CFillOptions) -> i32 {
+    let options = c_options(options);
+    to_status(c_str_path(file_path).and_then(|file_path| fill_wav_with(file_path, &options)))
+}
+
+/// Same as `zero_fill_any`, but with the given options, or the default ones if `options` is NULL.
+/// `detection` and `recovery` are ignored, as the whole file is always zero filled.
+///
+/// # Safety
+///
+/// `file_path` must either be NULL or point to a valid NUL-terminated C string, and `options`
+/// must either be NULL or point to options with valid `mode`, `detection` and `recovery` values.
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_any_with_options(file_path: *const c_char, options: *const CFillOptions) -> i32 {
+    let options = c_options(options);
+    to_status(c_str_path(file_path).and_then(|file_path| fill_any_with(file_path, &options)))
+}
+
+/// cbindgen:rename-all=QualifiedScreamingSnakeCase
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeUnit {
+    Seconds,
+    /// Sample frames, which must be whole numbers.
+    Frames,
+}
+
+/// A span of a recording for `zero_fill_wav_redact`, from `start` up to but not including `end`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CTimeRange {
+    pub unit: TimeUnit,
+    pub start: f64,
+    pub end: f64,
+}
+
+impl CTimeRange {
+    fn to_range(self) -> error::Result<TimeRange> {
+        let whole = |n: f64| n >= 0.0 && n.fract() == 0.0;
+
+        match self.unit {
+            TimeUnit::Seconds => Ok(TimeRange::Seconds(self.start, self.end)),
+            TimeUnit::Frames if whole(self.start) && whole(self.end) => Ok(TimeRange::Frames(self.start as u64, self.end as u64)),
+            TimeUnit::Frames => Err(Error::invalid_argument(format!("invalid time range frames {}-{}", self.start, self.end))),
+        }
+    }
+}
+
+/// Silences only the `count` given ranges of a WAV file, leaving the rest of the audio as it is,
+/// with the given options, or the default ones if `options` is NULL.
+///
+/// # Safety
+///
+/// `file_path` must either be NULL or point to a valid NUL-terminated C string, `ranges` must either be NULL
+/// or point to `count` ranges with valid `unit` values, and `options` must either be NULL or point to options
+/// with valid `mode`, `detection` and `recovery` values.
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_wav_redact(file_path: *const c_char, ranges: *const CTimeRange, count: usize,
+    options: *const CFillOptions) -> i32 {
+
+    if ranges.is_null() && count > 0 {
+        return to_status(Err(Error::new(ErrorKind::NullPointer)));
+    }
+
+    let options = match options.as_ref() {
+        Some(&options) => FillOptions::from(options),
+        None => FillOptions::default(),
+    };
+
+    let ranges = (0..count)
+        .map(|i| (*ranges.add(i)).to_range())
+        .collect::<error::Result<Vec<_>>>();
 
-const ZERO_FILL_OK: i32 = 0;
-const ZERO_FILL_C_STR_CONV: i32 = 1;
-const ZERO_FILL_FILENAME: i32 = 2;
-const ZERO_FILL_IO: i32 = 3;
-const ZERO_FILL_WAV: i32 = 4;
+    to_status(c_str_path(file_path).and_then(|file_path| redact::redact_wav_with(file_path, &ranges?, &options)))
+}
 
+/// Zero fills every file in `file_paths` using `threads` worker threads, or one per CPU if 0.
+///
+/// The status of each file is written into `statuses` unless it is NULL. Returns `ZERO_FILL_OK`
+/// if every file succeeded, otherwise the status of the first file that failed.
+///
+/// # Safety
+///
+/// `file_paths` must either be NULL or point to `count` pointers that are each NULL or point to
+/// a valid NUL-terminated C string, and `statuses` must either be NULL or point to space for `count` statuses.
 #[no_mangle]
-pub extern fn zero_fill_matching(file_path: *const c_char) -> i32 {
-    let file_path = unsafe { CStr::from_ptr(file_path).to_str() };
+pub unsafe extern "C" fn zero_fill_batch(file_paths: *const *const c_char, count: usize, threads: usize, statuses: *mut i32) -> i32 {
+    if file_paths.is_null() && count > 0 {
+        return to_status(Err(Error::new(ErrorKind::NullPointer)));
+    }
+
+    let file_paths: Vec<_> = (0..count)
+        .map(|i| c_str_path(*file_paths.add(i)))
+        .collect();
+
+    let valid_paths: Vec<_> = file_paths.iter().filter_map(|p| p.as_ref().ok()).collect();
+    let mut outcomes = batch::fill_batch(valid_paths, threads, &FillOptions::default()).into_iter();
+
+    let results: Vec<_> = file_paths.into_iter()
+        .map(|file_path| file_path.and_then(|_| outcomes.next().expect("one outcome per valid path").1))
+        .collect();
+
+    if !statuses.is_null() {
+        for (i, res) in results.iter().enumerate() {
+            *statuses.add(i) = res.as_ref().err().map_or(ZERO_FILL_OK, status_code);
+        }
+    }
+
+    to_status(results.into_iter().find(|res| res.is_err()).unwrap_or(Ok(())))
+}
 
-    let res = match file_path {
-        Ok(file_path) => fill_matching(file_path),
-        Err(e) => Err(Error::CStrConv(e)),
+/// Copies the message of the last error on the calling thread into `buf` as a NUL-terminated string,
+/// truncated to fit in `len` bytes.
+///
+/// Returns the length of the whole message without the NUL, so that a larger buffer can be
+/// passed if it did not fit, or 0 if the last call on the calling thread succeeded.
+///
+/// # Safety
+///
+/// `buf` must either be NULL or point to space for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zero_fill_last_error_message(buf: *mut c_char, len: usize) -> usize {
+    let message = match last_error::message() {
+        Some(message) => message,
+        None => return 0,
     };
 
+    if !buf.is_null() && len > 0 {
+        // truncates at a character boundary so that the copy is still valid UTF-8
+        let mut n = message.len().min(len - 1);
+
+        while !message.is_char_boundary(n) {
+            n -= 1;
+        }
+
+        ptr::copy_nonoverlapping(message.as_ptr() as *const c_char, buf, n);
+        *buf.add(n) = 0;
+    }
+
+    message.len()
+}
+
+/// Returns the OS error number behind the last error on the calling thread,
+/// or 0 if the last call succeeded or did not fail because of the OS.
+#[no_mangle]
+pub extern "C" fn zero_fill_last_os_error() -> i32 {
+    last_error::os_error()
+}
+
+unsafe fn c_fill_matching(file_path: *const c_char, options: &FillOptions) -> i32 {
+    to_status(c_str_path(file_path).and_then(|file_path| fill_matching_with(file_path, options)))
+}
+
+unsafe fn c_options(options: *const CFillOptions) -> FillOptions {
+    match options.as_ref() {
+        Some(&options) => FillOptions::from(options),
+        None => FillOptions::default(),
+    }
+}
+
+unsafe fn c_str_path<'a>(file_path: *const c_char) -> error::Result<&'a Path> {
+    if file_path.is_null() {
+        return Err(Error::new(ErrorKind::NullPointer));
+    }
+
+    let file_path = CStr::from_ptr(file_path);
+
+    // Unix paths are arbitrary bytes, so no encoding is required of them
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        Ok(Path::new(OsStr::from_bytes(file_path.to_bytes())))
+    }
+
+    #[cfg(not(unix))]
+    Ok(Path::new(file_path.to_str()?))
+}
+
+/// Maps the outcome of a C call into its status code, keeping the error for `zero_fill_last_error_message`.
+fn to_status(res: error::Result<()>) -> i32 {
     match res {
-        Ok(_) => ZERO_FILL_OK,
-        Err(e) => match e {
-            Error::CStrConv(_) => ZERO_FILL_C_STR_CONV,
-            Error::Filename => ZERO_FILL_FILENAME,
-            Error::IO(_) => ZERO_FILL_IO,
-            Error::Wav(_) => ZERO_FILL_WAV,
+        Ok(_) => {
+            last_error::clear();
+            ZERO_FILL_OK
+        },
+
+        Err(e) => {
+            last_error::set(&e);
+            status_code(&e)
         },
     }
 }
 
-enum ExtType {
-    Wav,
-    Others,
+/// Maps the error into its `ZERO_FILL_*` status code.
+pub fn status_code(e: &Error) -> i32 {
+    match *e.kind() {
+        ErrorKind::NullPointer => ZERO_FILL_NULL_POINTER,
+        ErrorKind::CStrConv(_) => ZERO_FILL_C_STR_CONV,
+        ErrorKind::Filename => ZERO_FILL_FILENAME,
+        ErrorKind::IO(_) => ZERO_FILL_IO,
+        ErrorKind::Format(_) => ZERO_FILL_FORMAT,
+        ErrorKind::Unsupported(_) => ZERO_FILL_UNSUPPORTED,
+        ErrorKind::Verify(_) => ZERO_FILL_VERIFY,
+        ErrorKind::InvalidArgument(_) => ZERO_FILL_INVALID_ARGUMENT,
+    }
 }
 
 pub fn fill_matching<P: AsRef<Path>>(file_path: P) -> error::Result<()> {
+    fill_matching_with(file_path, &FillOptions::default())
+}
+
+pub fn fill_matching_with<P: AsRef<Path>>(file_path: P, options: &FillOptions) -> error::Result<()> {
     let file_path = file_path.as_ref();
+    let filler = registry::find(file_path, options.detection).for_path(file_path)?;
+    verify::fill_verified(&*filler, file_path, options)
+}
 
-    let ext_type = match file_path.extension() {
-        Some(ext) => {
-            let ext = ext.to_string_lossy().to_lowercase();
+/// cbindgen:rename-all=QualifiedScreamingSnakeCase
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Recreates the file with the zero filled content.
+    Rewrite,
+    /// Overwrites the bytes to be zero filled in the existing file, leaving everything else as it is.
+    InPlace,
+}
 
-            match ext.as_ref() {
-                "wav" => ExtType::Wav,
-                _ => ExtType::Others,
-            }
-        },
+/// cbindgen:rename-all=QualifiedScreamingSnakeCase
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Recovery {
+    /// Fails on WAV files whose sizes do not match the file.
+    Strict,
+    /// Fills WAV files whose `data` size is 0, 0xFFFFFFFF or past the end of the file up to the end
+    /// of the file, as left by a recorder that stopped before writing the sizes.
+    Lenient,
+    /// Same as `Lenient`, and also corrects the RIFF and `data` sizes of the recovered files.
+    Repair,
+}
 
-        None => ExtType::Others,
-    };
+#[derive(Clone, Debug)]
+pub struct FillOptions {
+    pub mode: Mode,
+    /// Writes `Mode::Rewrite` fills to a temporary file that is renamed over the original,
+    /// so that the original is never left half written. Has no effect on `Mode::InPlace`.
+    /// Rewrites that keep more than the headers of the file, such as redactions, are always atomic.
+    pub atomic: bool,
+    /// Size of the buffer used to write the zeroes, which bounds the memory used regardless of file size.
+    pub buffer_size: usize,
+    /// How `fill_matching` picks the filler for a file.
+    pub detection: Detection,
+    /// Re-reads every file after filling it, failing with `Error::Verify` if the result is off.
+    pub verify: bool,
+    pub recovery: Recovery,
+}
 
-    match ext_type {
-        ExtType::Wav => fill_wav(file_path),
-        ExtType::Others => fill_any(file_path),
+impl Default for FillOptions {
+    fn default() -> FillOptions {
+        FillOptions {
+            mode: Mode::Rewrite,
+            atomic: false,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            detection: Detection::Auto,
+            verify: false,
+            recovery: Recovery::Strict,
+        }
     }
 }
 
 pub fn fill_wav<P: AsRef<Path>>(file_path: P) -> error::Result<()> {
-    let file_path = file_path.as_ref();
+    fill_wav_with(file_path, &FillOptions::default())
+}
+
+/// Replaces the samples of a WAV file with silence, keeping every other chunk as it is.
+/// RF64 and BW64 files keep their container and `ds64` chunk, so files over 4 GiB stay valid.
+pub fn fill_wav_with<P: AsRef<Path>>(file_path: P, options: &FillOptions) -> error::Result<()> {
+    verify::fill_verified(&WavFiller, file_path.as_ref(), options)
+}
+
+/// Same as `fill_matching_with`, but for an open stream such as a file or `Cursor`, choosing between
+/// `fill_wav_stream`, `aiff::fill_aiff_stream` and `fill_any_stream` by the content alone.
+/// FLAC streams are rejected, as they cannot be truncated to the length of the re-encoded silence,
+/// and so is other audio these functions do not handle, such as RIFX and Ogg.
+pub fn fill_matching_stream<S: Read + Write + Seek>(stream: &mut S, options: &FillOptions) -> error::Result<()> {
+    stream.seek(SeekFrom::Start(0)).during(Operation::ReadHeader)?;
+    let head = detect::read_head_from(&mut *stream).during(Operation::ReadHeader)?;
+
+    match detect::sniff(&head) {
+        Some(Format::Wav) | Some(Format::Rf64) | Some(Format::Bw64) => fill_wav_stream(stream, options),
+        Some(Format::Aiff) | Some(Format::Aifc) => aiff::fill_aiff_stream(stream, options),
+        // zeroing the rest of the audio whole would leave it undecodable
+        Some(format) if format.is_audio() => Err(Error::unsupported(format!("filling {:?} streams", format))),
+        _ => fill_any_stream(stream, options),
+    }
+}
+
+/// Same as `fill_wav_with`, but for an open stream such as a file or `Cursor`.
+///
+/// The stream is always filled in place and is not verified, so only `options.buffer_size`
+/// and `options.recovery` are used.
+pub fn fill_wav_stream<S: Read + Write + Seek>(stream: &mut S, options: &FillOptions) -> error::Result<()> {
+    let wav = wav::read_wav(stream, options.recovery).during(Operation::ReadHeader)?;
+
+    let repairs = match options.recovery {
+        Recovery::Repair => wav::size_repairs(&wav, stream.seek(SeekFrom::End(0)).during(Operation::Read)?)?,
+        Recovery::Strict | Recovery::Lenient => Vec::new(),
+    };
+
+    stream.seek(SeekFrom::Start(wav.data.start)).during(Operation::Write)?;
+    write_repeated(stream, wav.silence, wav.data.end - wav.data.start, options.buffer_size).during(Operation::Write)?;
+    write_patches(stream, &repairs).during(Operation::Write)?;
+    stream.flush().during(Operation::Finalize)
+}
+
+/// Returns a copy of the WAV file held in `data`, with its samples replaced by silence.
+pub fn fill_wav_buffer(data: &[u8]) -> error::Result<Vec<u8>> {
+    let mut cursor = Cursor::new(data.to_vec());
+    fill_wav_stream(&mut cursor, &FillOptions::default())?;
+    Ok(cursor.into_inner())
+}
+
+fn fill_wav_unverified(file_path: &Path, options: &FillOptions) -> error::Result<()> {
+    // planned before anything is written so that unsupported files are left untouched
+    let (plan, repairs) = plan_wav_repairs(file_path, options)?;
+    fill_regions(file_path, &plan.regions, &repairs, options)
+}
+
+pub fn plan_wav<P: AsRef<Path>>(file_path: P) -> error::Result<Plan> {
+    plan_wav_with(file_path, &FillOptions::default())
+}
+
+/// Same as `plan_wav`, but recovering files as set by `options.recovery`.
+pub fn plan_wav_with<P: AsRef<Path>>(file_path: P, options: &FillOptions) -> error::Result<Plan> {
+    plan_wav_repairs(file_path.as_ref(), options).map(|(plan, _)| plan)
+}
+
+fn plan_wav_repairs(file_path: &Path, options: &FillOptions) -> error::Result<(Plan, Vec<Patch>)> {
+    let plan = || -> error::Result<(Plan, Vec<Patch>)> {
+        let (wav, len, repairs) = read_wav_repairs(file_path, options)?;
+
+        let plan = Plan {
+            filler: "wav".to_owned(),
+            regions: vec![Region { range: wav.data.clone(), fill: wav.silence }],
+            len,
+            structure: Some(wav::describe(&wav)),
+        };
+
+        Ok((plan, repairs))
+    };
+
+    plan().for_path(file_path)
+}
 
-    let (spec, len) = {
-        let reader = WavReader::open(file_path)?;
-        (reader.spec().clone(), reader.len())
+/// Reads the WAV file as set by `options.recovery`, returning it with the file length and the writes
+/// that correct its sizes, which are only made for `Recovery::Repair`.
+fn read_wav_repairs(file_path: &Path, options: &FillOptions) -> error::Result<(wav::Wav, u64, Vec<Patch>)> {
+    let mut file = File::open(file_path).during(Operation::Open)?;
+    let wav = wav::read_wav(&mut file, options.recovery).during(Operation::ReadHeader)?;
+    let len = file.metadata().during(Operation::Read)?.len();
+
+    let repairs = match options.recovery {
+        Recovery::Repair => wav::size_repairs(&wav, len)?,
+        Recovery::Strict | Recovery::Lenient => Vec::new(),
+    };
+
+    Ok((wav, len, repairs))
+}
+
+/// Verifies the filled WAV file against a plan made before filling, requiring the spec
+/// and sample count to be unchanged and the data chunk to hold only silence.
+pub fn verify_wav<P: AsRef<Path>>(file_path: P, plan: &Plan) -> error::Result<Verification> {
+    // replanned leniently, so that recovered files that were not repaired still verify
+    let options = FillOptions {
+        recovery: Recovery::Lenient,
+        ..FillOptions::default()
     };
 
-    let mut writer = WavWriter::create(file_path, spec)?;
+    verify::verify_replanned(file_path, plan, |file_path| plan_wav_with(file_path, &options))
+}
+
+/// Bytes written over the kept part of a file, such as a corrected size field in a header.
+struct Patch {
+    offset: u64,
+    bytes: Vec<u8>,
+}
+
+/// Overwrites every region of the file with its fill byte and writes the patches, keeping all other
+/// bytes as they are. The regions must be sorted and must not overlap, and no patch may overlap a region.
+fn fill_regions(file_path: &Path, regions: &[Region], patches: &[Patch], options: &FillOptions) -> error::Result<()> {
+    match options.mode {
+        Mode::Rewrite => {
+            let mut file = File::open(file_path).during(Operation::Open)?;
+            let len = file.metadata().during(Operation::Read)?.len();
+            let kept_len = len - regions.iter().map(|region| region.range.end - region.range.start).sum::<u64>();
+
+            if options.atomic || kept_len > MAX_KEPT_IN_MEMORY {
+                // the original stays in place until it is replaced, so the kept bytes are copied from it piece by piece
+                return replace::replace_with(file_path, true, |writer| {
+                    let mut start = 0;
+
+                    for region in regions {
+                        copy_range(&mut file, writer, start..region.range.start, patches, options.buffer_size)?;
+                        write_repeated(writer, region.fill, region.range.end - region.range.start, options.buffer_size)?;
+                        start = region.range.end;
+                    }
+
+                    Ok(copy_range(&mut file, writer, start..len, patches, options.buffer_size)?)
+                });
+            }
+
+            // the kept bytes are read up front, as rewriting without `atomic` truncates the original
+            let mut kept = Vec::with_capacity(regions.len() + 1);
+            let mut start = 0;
+
+            for region in regions.iter().map(|region| region.range.clone()).chain(Some(len..len)) {
+                let mut segment = Vec::new();
+                copy_range(&mut file, &mut segment, start..region.start, patches, options.buffer_size).during(Operation::Read)?;
+                kept.push(segment);
+                start = region.end;
+            }
+
+            replace::replace_with(file_path, false, |writer| {
+                for (segment, region) in kept.iter().zip(regions) {
+                    writer.write_all(segment)?;
+                    write_repeated(writer, region.fill, region.range.end - region.range.start, options.buffer_size)?;
+                }
+
+                Ok(writer.write_all(kept.last().expect("one more kept segment than regions"))?)
+            })
+        },
+
+        Mode::InPlace => {
+            let mut file = OpenOptions::new().write(true).open(file_path).during(Operation::Open)?;
+
+            for region in regions {
+                file.seek(SeekFrom::Start(region.range.start)).during(Operation::Write)?;
+                write_repeated(&mut file, region.fill, region.range.end - region.range.start, options.buffer_size)
+                    .during(Operation::Write)?;
+            }
+
+            // patched last, so that a repaired header never describes audio that was not yet filled
+            write_patches(&mut file, patches).during(Operation::Write)?;
+            file.sync_all().during(Operation::Finalize)
+        },
+    }
+}
+
+fn write_patches<W: Write + Seek>(writer: &mut W, patches: &[Patch]) -> io::Result<()> {
+    for patch in patches {
+        writer.seek(SeekFrom::Start(patch.offset))?;
+        writer.write_all(&patch.bytes)?;
+    }
+
+    Ok(())
+}
+
+/// Copies a range of the reader to the writer through a buffer of at most `buffer_size` bytes,
+/// with the patches that fall within the range written over the copied bytes.
+fn copy_range<R: Read + Seek, W: Write>(reader: &mut R, writer: &mut W, range: Range<u64>, patches: &[Patch],
+    buffer_size: usize) -> io::Result<()> {
+
+    let mut buf = vec![0u8; (buffer_size.max(1) as u64).min(range.end - range.start) as usize];
+    let mut offset = range.start;
+    reader.seek(SeekFrom::Start(offset))?;
+
+    while offset < range.end {
+        let piece_len = (range.end - offset).min(buf.len() as u64) as usize;
+        let piece = &mut buf[..piece_len];
+        reader.read_exact(piece)?;
+
+        for patch in patches {
+            let start = patch.offset.max(offset);
+            let end = (patch.offset + patch.bytes.len() as u64).min(offset + piece.len() as u64);
+
+            if start < end {
+                piece[(start - offset) as usize..(end - offset) as usize]
+                    .copy_from_slice(&patch.bytes[(start - patch.offset) as usize..(end - patch.offset) as usize]);
+            }
+        }
+
+        writer.write_all(piece)?;
+        offset += piece.len() as u64;
+    }
+
+    Ok(())
+}
+
+fn write_repeated<W: Write>(writer: &mut W, byte: u8, len: u64, buffer_size: usize) -> error::Result<()> {
+    let buffer_size = (buffer_size.max(1) as u64).min(len) as usize;
+    let buf = vec![byte; buffer_size];
+    let mut remaining = len;
 
-    for _ in 0..len {
-        writer.write_sample(0 as i16)?;
+    while remaining > 0 {
+        let n = remaining.min(buf.len() as u64) as usize;
+        writer.write_all(&buf[..n])?;
+        remaining -= n as u64;
     }
-    
-    Ok(writer.finalize()?)
+
+    Ok(())
 }
 
 pub fn fill_any<P: AsRef<Path>>(file_path: P) -> error::Result<()> {
+    fill_any_with(file_path, &FillOptions::default())
+}
+
+pub fn plan_any<P: AsRef<Path>>(file_path: P) -> error::Result<Plan> {
     let file_path = file_path.as_ref();
+    let len = fs::metadata(file_path).during(Operation::Open).for_path(file_path)?.len();
+    Ok(Plan::whole_file("any", len))
+}
+
+pub fn verify_any<P: AsRef<Path>>(file_path: P, plan: &Plan) -> error::Result<Verification> {
+    verify::verify_regions(file_path, plan)
+}
+
+pub fn fill_any_with<P: AsRef<Path>>(file_path: P, options: &FillOptions) -> error::Result<()> {
+    verify::fill_verified(&AnyFiller, file_path.as_ref(), options)
+}
+
+/// Same as `fill_any_with`, but for an open stream such as a file or `Cursor`.
+///
+/// The stream is always filled in place and is not verified, so only `options.buffer_size` is used.
+pub fn fill_any_stream<S: Write + Seek>(stream: &mut S, options: &FillOptions) -> error::Result<()> {
+    let len = stream.seek(SeekFrom::End(0)).during(Operation::Read)?;
+    stream.seek(SeekFrom::Start(0)).during(Operation::Write)?;
+
+    write_repeated(stream, 0, len, options.buffer_size).during(Operation::Write)?;
+    stream.flush().during(Operation::Finalize)
+}
+
+/// Returns a zero filled buffer of the same size as `data`.
+pub fn fill_any_buffer(data: &[u8]) -> Vec<u8> {
+    vec![0; data.len()]
+}
+
+fn fill_any_unverified(file_path: &Path, options: &FillOptions) -> error::Result<()> {
+
+    let len = fs::metadata(file_path).during(Operation::Open)?.len();
 
-    let metadata = fs::metadata(file_path)?;
-    let len = metadata.len();
+    match options.mode {
+        Mode::Rewrite => replace::replace_with(file_path, options.atomic, |writer| {
+            write_repeated(writer, 0, len, options.buffer_size)
+        }),
 
-    let mut file = File::create(file_path)?;
-    Ok(file.write_all(&vec![0u8; len as usize])?)
+        Mode::InPlace => {
+            // no truncation, so that the zeroes are written over the blocks of the original data
+            let mut file = OpenOptions::new().write(true).open(file_path).during(Operation::Open)?;
+            write_repeated(&mut file, 0, len, options.buffer_size).during(Operation::Write)?;
+            file.sync_all().during(Operation::Finalize)
+        },
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use super::test_util::{aiff_vec, fill_file};
+    use hound::{SampleFormat, WavReader, WavWriter};
+    use cbindgen;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::env;
+    use std::ffi::CString;
     use std::fs;
-    use std::io::Read;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // records the largest single allocation so that tests can check memory use stays bounded
+    struct LargestAlloc;
+
+    static LARGEST_ALLOC: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe impl GlobalAlloc for LargestAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            LARGEST_ALLOC.fetch_max(layout.size(), Ordering::SeqCst);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: LargestAlloc = LargestAlloc;
 
     fn wav_vec() -> Vec<u8> {
         vec![0x52, 0x49, 0x46, 0x46, 0x06, 0x0F, 0x00, 0x00, 0x57, 0x41, 0x56, 0x45, 0x66, 0x6D, 0x74, 0x20, 0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x11, 0x2B, 0x00, 0x00, 0x11, 0x2B, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x64, 0x61, 0x74, 0x61, 0xE1, 0x0E, 0x00, 0x00, 0x81, 0x81, 0x80, 0x82, 0x80, 0x82, 0x80, 0x82, 0x81, 0x82, 0x82, 0x82, 0x82, 0x81, 0x82, 0x81, 0x82, 0x81, 0x82, 0x81, 0x82, 0x81, 0x81, 0x82, 0x81, 0x82, 0x81, 0x83, 0x81, 0x83, 0x81, 0x82, 0x82, 0x82, 0x83, 0x82, 0x83, 0x81, 0x82, 0x81, 0x83, 0x82, 0x84, 0x83, 0x84, 0x85, 0x85, 0x86, 0x86, 0x88, 0x86, 0x86, 0x83, 0x84, 0x81, 0x81, 0x80, 0x7E, 0x7E, 0x7D, 0x7E, 0x7D, 0x7F, 0x7E, 0x7F, 0x7F, 0x7F, 0x80, 0x80, 0x82, 0x81, 0x83, 0x83, 0x84, 0x84, 0x84, 0x85, 0x84, 0x86, 0x86, 0x87, 0x87, 0x88, 0x88, 0x87, 0x89, 0x87, 0x89, 0x87, 0x88, 0x88, 0x87, 0x87, 0x85, 0x85, 0x83, 0x84, 0x83, 0x83, 0x83, 0x81, 0x83, 0x82, 0x84, 0x82, 0x83, 0x83, 0x83, 0x83, 0x81, 0x82, 0x82, 0x84, 0x81, 0x81, 0x81, 0x82, 0x84, 0x83, 0x85, 0x84, 0x86, 0x87, 0x8A, 0x8D, 0x8E, 0x8F, 0x8B, 0x89, 0x85, 0x85, 0x82, 0x80, 0x7E, 0x7C, 0x7D, 0x7B, 0x7D, 0x7C, 0x7E, 0x7E, 0x7F, 0x7F, 0x80, 0x82, 0x81, 0x83, 0x82, 0x83, 0x82, 0x82, 0x83, 0x83, 0x85, 0x84, 0x86, 0x86, 0x8A, 0x8A, 0x8D, 0x8D, 0x8C, 0x8C, 0x8A, 0x8B, 0x89, 0x89, 0x86, 0x84, 0x80, 0x7F, 0x7F, 0x7F, 0x7F, 0x7C, 0x7D, 0x7B, 0x7E, 0x7E, 0x81, 0x82, 0x81, 0x81, 0x81, 0x84, 0x84, 0x85, 0x82, 0x81, 0x80, 0x83, 0x83, 0x84, 0x87, 0x89, 0x8C, 0x8C, 0x92, 0x95, 0x99, 0x95, 0x92, 0x8C, 0x89, 0x86, 0x81, 0x7F, 0x7C, 0x7C, 0x7B, 0x7D, 0x7C, 0x7D, 0x7B, 0x7D, 0x7D, 0x7E, 0x7F, 0x7F, 0x81, 0x80, 0x82, 0x81, 0x83, 0x83, 0x85, 0x84, 0x86, 0x87, 0x89, 0x8B, 0x8B, 0x8C, 0x8B, 0x8C, 0x8A, 0x8B, 0x88, 0x88, 0x86, 0x84, 0x83, 0x80, 0x80, 0x7D, 0x7D, 0x7C, 0x7D, 0x7D, 0x7F, 0x80, 0x80, 0x81, 0x7F, 0x81, 0x81, 0x84, 0x85, 0x83, 0x81, 0x7E, 0x7F, 0x80, 0x83, 0x85, 0x89, 0x8F, 0x92, 0x97, 0x99, 0x9D, 0x9A, 0x95, 0x8E, 0x88, 0x84, 0x7E, 0x7C, 0x78, 0x79, 0x79, 0x7A, 0x7B, 0x7A, 0x7A, 0x78, 0x78, 0x77, 0x78, 0x78, 0x78, 0x7B, 0x7C, 0x80, 0x80, 0x84, 0x85, 0x87, 0x88, 0x89, 0x8C, 0x8C, 0x8E, 0x8D, 0x8E, 0x8F, 0x8F, 0x8E, 0x8A, 0x8A, 0x87, 0x87, 0x83, 0x83, 0x81, 0x7E, 0x7D, 0x7B, 0x7D, 0x7D, 0x80, 0x7F, 0x7E, 0x7D, 0x7D, 0x7F, 0x7F, 0x80, 0x7E, 0x80, 0x81, 0x85, 0x89, 0x8A, 0x8D, 0x8F, 0x95, 0x98, 0x9C, 0x9B, 0x98, 0x92, 0x8B, 0x86, 0x80, 0x7D, 0x78, 0x76, 0x74, 0x74, 0x73, 0x73, 0x75, 0x77, 0x7A, 0x78, 0x79, 0x79, 0x7C, 0x7C, 0x7E, 0x81, 0x84, 0x89, 0x89, 0x8D, 0x8D, 0x8F, 0x8C, 0x8D, 0x8D, 0x8E, 0x8E, 0x8D, 0x8E, 0x8C, 0x8A, 0x85, 0x87, 0x85, 0x82, 0x7D, 0x7B, 0x7A, 0x78, 0x7A, 0x7B, 0x7F, 0x7D, 0x7B, 0x78, 0x78, 0x77, 0x74, 0x72, 0x74, 0x7B, 0x81, 0x88, 0x8B, 0x93, 0x97, 0x9C, 0x9E, 0x9E, 0x9C, 0x93, 0x8B, 0x83, 0x80, 0x7C, 0x7D, 0x7C, 0x7C, 0x79, 0x79, 0x78, 0x75, 0x74, 0x73, 0x76, 0x76, 0x79, 0x7A, 0x7E, 0x80, 0x82, 0x83, 0x84, 0x88, 0x8A, 0x8F, 0x92, 0x96, 0x96, 0x96, 0x95, 0x92, 0x8F, 0x89, 0x87, 0x84, 0x84, 0x83, 0x81, 0x81, 0x80, 0x80, 0x7E, 0x80, 0x80, 0x7E, 0x7A, 0x77, 0x76, 0x73, 0x72, 0x6E, 0x70, 0x74, 0x7B, 0x82, 0x87, 0x90, 0x95, 0x9A, 0x99, 0x9C, 0x9C, 0x96, 0x8E, 0x86, 0x83, 0x7F, 0x7E, 0x7C, 0x7C, 0x7C, 0x7B, 0x7B, 0x79, 0x7A, 0x77, 0x76, 0x74, 0x75, 0x78, 0x78, 0x7A, 0x7C, 0x81, 0x82, 0x87, 0x8C, 0x8E, 0x8E, 0x8B, 0x8C, 0x88, 0x85, 0x82, 0x85, 0x88, 0x87, 0x88, 0x89, 0x8A, 0x84, 0x81, 0x7B, 0x7B, 0x79, 0x79, 0x7B, 0x7F, 0x85, 0x85, 0x85, 0x82, 0x7E, 0x78, 0x74, 0x76, 0x7A, 0x84, 0x8A, 0x94, 0x99, 0x9E, 0x9C, 0x94, 0x8B, 0x83, 0x7F, 0x7C, 0x7F, 0x7F, 0x7D, 0x79, 0x7A, 0x7A, 0x79, 0x7B, 0x7C, 0x7F, 0x7E, 0x80, 0x80, 0x82, 0x82, 0x82, 0x80, 0x83, 0x88, 0x8A, 0x8B, 0x8B, 0x8F, 0x90, 0x90, 0x8C, 0x8A, 0x87, 0x80, 0x7C, 0x7C, 0x83, 0x81, 0x7E, 0x7D, 0x7F, 0x78, 0x70, 0x6F, 0x76, 0x7C, 0x81, 0x8F, 0x9C, 0x9A, 0x84, 0x79, 0x77, 0x70, 0x62, 0x6D, 0x90, 0xA8, 0xA5, 0x97, 0x91, 0x88, 0x81, 0x7C, 0x7B, 0x75, 0x70, 0x6C, 0x6E, 0x71, 0x78, 0x82, 0x86, 0x85, 0x7D, 0x7B, 0x78, 0x75, 0x76, 0x83, 0x95, 0xA1, 0xA5, 0x9F, 0x98, 0x89, 0x7D, 0x75, 0x75, 0x7D, 0x8A, 0x96, 0x96, 0x96, 0x8C, 0x7A, 0x65, 0x5E, 0x68, 0x73, 0x85, 0x9A, 0xAA, 0x9E, 0x83, 0x6E, 0x64, 0x5A, 0x52, 0x6B, 0x98, 0xB5, 0xAA, 0x96, 0x8E, 0x8A, 0x85, 0x7D, 0x79, 0x75, 0x75, 0x77, 0x79, 0x7E, 0x7E, 0x7E, 0x7A, 0x78, 0x77, 0x7B, 0x85, 0x8E, 0x95, 0x94, 0x94, 0x8F, 0x86, 0x7E, 0x7D, 0x85, 0x88, 0x8B, 0x8E, 0x92, 0x8C, 0x7C, 0x72, 0x74, 0x7B, 0x7A, 0x83, 0x97, 0xA4, 0x94, 0x77, 0x68, 0x64, 0x5E, 0x56, 0x6B, 0x93, 0xA8, 0x9E, 0x8B, 0x86, 0x80, 0x7C, 0x7A, 0x7C, 0x7A, 0x7A, 0x7D, 0x7F, 0x81, 0x7D, 0x7D, 0x7B, 0x7D, 0x7E, 0x82, 0x86, 0x8C, 0x8F, 0x89, 0x84, 0x7F, 0x83, 0x82, 0x80, 0x81, 0x88, 0x8D, 0x86, 0x81, 0x82, 0x87, 0x80, 0x7C, 0x85, 0x98, 0x9A, 0x87, 0x77, 0x74, 0x75, 0x6C, 0x71, 0x89, 0xA4, 0xA4, 0x96, 0x8C, 0x89, 0x86, 0x7F, 0x7C, 0x76, 0x76, 0x77, 0x7A, 0x7B, 0x7B, 0x7C, 0x7D, 0x7C, 0x7B, 0x81, 0x88, 0x8C, 0x87, 0x84, 0x82, 0x7F, 0x7B, 0x7A, 0x80, 0x85, 0x8B, 0x89, 0x88, 0x8A, 0x90, 0x88, 0x7E, 0x7C, 0x89, 0x91, 0x87, 0x79, 0x71, 0x70, 0x65, 0x60, 0x71, 0x95, 0xA8, 0xA2, 0x96, 0x8F, 0x89, 0x7F, 0x7B, 0x78, 0x7A, 0x7A, 0x7D, 0x7C, 0x7D, 0x7E, 0x7C, 0x7D, 0x80, 0x87, 0x89, 0x88, 0x85, 0x82, 0x7E, 0x75, 0x71, 0x74, 0x81, 0x86, 0x87, 0x86, 0x88, 0x89, 0x80, 0x7B, 0x7F, 0x8C, 0x8D, 0x83, 0x7F, 0x82, 0x81, 0x75, 0x79, 0x8E, 0xA1, 0x9D, 0x90, 0x89, 0x84, 0x7F, 0x78, 0x79, 0x7A, 0x77, 0x76, 0x78, 0x7C, 0x7A, 0x7C, 0x7D, 0x80, 0x81, 0x82, 0x82, 0x7D, 0x7A, 0x75, 0x77, 0x7C, 0x8A, 0x93, 0x8E, 0x88, 0x89, 0x8E, 0x89, 0x86, 0x87, 0x87, 0x7D, 0x6D, 0x6B, 0x73, 0x7A, 0x73, 0x7A, 0x91, 0xA0, 0x98, 0x89, 0x87, 0x81, 0x79, 0x76, 0x7A, 0x77, 0x71, 0x72, 0x74, 0x7A, 0x7F, 0x88, 0x8A, 0x87, 0x7C, 0x76, 0x7B, 0x80, 0x83, 0x86, 0x8F, 0x8E, 0x86, 0x7F, 0x84, 0x8A, 0x87, 0x7F, 0x7B, 0x80, 0x80, 0x7E, 0x7C, 0x80, 0x82, 0x86, 0x91, 0xA0, 0xA8, 0x96, 0x7C, 0x6C, 0x72, 0x78, 0x7D, 0x85, 0x88, 0x80, 0x74, 0x75, 0x79, 0x7F, 0x83, 0x86, 0x83, 0x82, 0x7E, 0x74, 0x6E, 0x75, 0x86, 0x8C, 0x8D, 0x95, 0x9D, 0x89, 0x71, 0x76, 0x8F, 0x93, 0x87, 0x85, 0x7D, 0x6C, 0x62, 0x73, 0x8D, 0xA4, 0x9E, 0x8C, 0x86, 0x88, 0x84, 0x7D, 0x84, 0x82, 0x73, 0x6A, 0x77, 0x81, 0x86, 0x87, 0x84, 0x7F, 0x78, 0x6C, 0x6E, 0x92, 0xAD, 0xA7, 0x91, 0x8A, 0x78, 0x63, 0x6D, 0x8C, 0x94, 0x81, 0x77, 0x70, 0x5F, 0x4D, 0x62, 0x9A, 0xC3, 0xBF, 0xAB, 0xA2, 0x8B, 0x69, 0x52, 0x5E, 0x7F, 0x9B, 0xA6, 0x95, 0x6F, 0x4F, 0x58, 0x74, 0x8C, 0xA7, 0xBA, 0xB1, 0x8A, 0x6F, 0x5A, 0x47, 0x5C, 0xA2, 0xCC, 0xAF, 0x87, 0x66, 0x30, 0x1D, 0x6B, 0xD5, 0xEA, 0xB9, 0x8A, 0x6B, 0x58, 0x64, 0x78, 0x7E, 0x80, 0x7E, 0x82, 0x79, 0x5D, 0x4C, 0x5C, 0x7A, 0xA2, 0xD3, 0xC6, 0x7C, 0x38, 0x29, 0x48, 0x97, 0xEB, 0xE0, 0x91, 0x4E, 0x25, 0x2B, 0x86, 0xEC, 0xE0, 0x8E, 0x6F, 0x7F, 0x84, 0x7F, 0x74, 0x66, 0x6C, 0x89, 0x9A, 0x76, 0x4C, 0x4A, 0x63, 0x98, 0xD9, 0xCF, 0x73, 0x2E, 0x28, 0x58, 0xB3, 0xF2, 0xC3, 0x6F, 0x45, 0x2C, 0x4F, 0xB5, 0xE9, 0xAD, 0x7C, 0x8D, 0x9D, 0x8F, 0x73, 0x5D, 0x61, 0x8B, 0xAB, 0x90, 0x52, 0x3C, 0x5D, 0xA4, 0xE1, 0xC1, 0x62, 0x23, 0x36, 0x7C, 0xD4, 0xE5, 0xA0, 0x5B, 0x2E, 0x34, 0x83, 0xD9, 0xC9, 0x8C, 0x8C, 0xA3, 0x9B, 0x7A, 0x55, 0x4E, 0x7D, 0xB3, 0xA8, 0x5A, 0x2A, 0x44, 0x97, 0xDB, 0xC8, 0x6A, 0x23, 0x34, 0x81, 0xD4, 0xE2, 0xA4, 0x55, 0x27, 0x3B, 0x99, 0xDC, 0xBA, 0x8E, 0x95, 0xA6, 0x96, 0x6C, 0x49, 0x62, 0xA3, 0xC1, 0x86, 0x33, 0x2E, 0x7B, 0xD0, 0xD5, 0x88, 0x30, 0x28, 0x6F, 0xC7, 0xE7, 0xAE, 0x59, 0x1D, 0x32, 0x97, 0xE0, 0xBB, 0x88, 0x91, 0xA9, 0x99, 0x5E, 0x36, 0x57, 0xAE, 0xC4, 0x74, 0x21, 0x30, 0x8D, 0xD2, 0xC5, 0x68, 0x22, 0x34, 0x8B, 0xDC, 0xDA, 0x8A, 0x34, 0x18, 0x59, 0xC8, 0xDC, 0x98, 0x7F, 0x9C, 0xAB, 0x7D, 0x3D, 0x37, 0x87, 0xCD, 0xA3, 0x44, 0x26, 0x6B, 0xC4, 0xD1, 0x84, 0x2A, 0x26, 0x77, 0xD5, 0xE0, 0x91, 0x32, 0x16, 0x5D, 0xCE, 0xDD, 0x8D, 0x76, 0xA2, 0xBB, 0x86, 0x30, 0x26, 0x78, 0xD4, 0xAE, 0x46, 0x20, 0x62, 0xBF, 0xD5, 0x86, 0x2C, 0x2D, 0x80, 0xDC, 0xDC, 0x80, 0x28, 0x23, 0x80, 0xDE, 0xC6, 0x7A, 0x85, 0xBA, 0xB0, 0x5D, 0x1E, 0x45, 0xAF, 0xD9, 0x7A, 0x24, 0x32, 0x8C, 0xD1, 0xB6, 0x57, 0x2A, 0x5C, 0xBB, 0xE4, 0xA4, 0x43, 0x1E, 0x5F, 0xC9, 0xD3, 0x8F, 0x85, 0xB7, 0xAE, 0x59, 0x20, 0x51, 0xBD, 0xDC, 0x7B, 0x21, 0x30, 0x8F, 0xDF, 0xC8, 0x62, 0x24, 0x4F, 0xB7, 0xEB, 0xB7, 0x4E, 0x17, 0x4B, 0xC3, 0xDE, 0xA5, 0x99, 0xB0, 0x83, 0x34, 0x2E, 0x86, 0xDD, 0xA6, 0x39, 0x17, 0x61, 0xC5, 0xD4, 0x7E, 0x29, 0x36, 0x94, 0xE4, 0xC6, 0x5A, 0x0E, 0x2C, 0xA3, 0xEE, 0xB4, 0x8C, 0x98, 0x7B, 0x3F, 0x49, 0xA4, 0xD2, 0x85, 0x1F, 0x1C, 0x72, 0xD2, 0xCE, 0x75, 0x24, 0x2E, 0x92, 0xE6, 0xCF, 0x5C, 0x08, 0x1C, 0x9B, 0xF7, 0xC3, 0x8B, 0x91, 0x79, 0x48, 0x68, 0xB8, 0xAE, 0x40, 0x04, 0x3D, 0xB0, 0xE5, 0x99, 0x2C, 0x10, 0x5F, 0xDD, 0xEC, 0x80, 0x0E, 0x04, 0x65, 0xEA, 0xEE, 0x92, 0x7D, 0x73, 0x5B, 0x7E, 0xB7, 0x94, 0x2B, 0x0E, 0x5C, 0xCF, 0xD9, 0x70, 0x12, 0x1E, 0x89, 0xEE, 0xDC, 0x61, 0x05, 0x14, 0x84, 0xF5, 0xDA, 0x7D, 0x69, 0x7E, 0x8E, 0xAC, 0x96, 0x45, 0x1A, 0x51, 0xBA, 0xDD, 0x92, 0x2C, 0x21, 0x76, 0xDB, 0xEB, 0x8D, 0x21, 0x11, 0x67, 0xDC, 0xF9, 0xA1, 0x5D, 0x79, 0xAD, 0xC0, 0x97, 0x4C, 0x28, 0x5A, 0xAD, 0xCC, 0x93, 0x40, 0x2F, 0x76, 0xCF, 0xDF, 0x9C, 0x37, 0x18, 0x5A, 0xBB, 0xE7, 0xA5, 0x6A, 0x95, 0xDE, 0xC4, 0x6F, 0x1D, 0x22, 0x7C, 0xC0, 0xB2, 0x7E, 0x47, 0x42, 0x83, 0xC1, 0xCA, 0x90, 0x3D, 0x29, 0x5B, 0xB2, 0xDC, 0xBC, 0xAA, 0xC3, 0xB1, 0x64, 0x22, 0x2E, 0x87, 0xC4, 0xA2, 0x57, 0x2C, 0x3D, 0x89, 0xCD, 0xCD, 0x8E, 0x32, 0x12, 0x45, 0xA9, 0xF5, 0xE0, 0xA6, 0x8D, 0x7B, 0x60, 0x65, 0x7A, 0x73, 0x50, 0x44, 0x54, 0x7C, 0xA4, 0xAF, 0x99, 0x6C, 0x45, 0x47, 0x73, 0xAB, 0xCD, 0xB3, 0x8E, 0x81, 0x7B, 0x70, 0x6B, 0x6B, 0x67, 0x67, 0x66, 0x66, 0x60, 0x62, 0x6E, 0x83, 0x96, 0x99, 0x91, 0x8A, 0x89, 0x83, 0x80, 0x7E, 0x7D, 0x7B, 0x7B, 0x7E, 0x7E, 0x80, 0x7F, 0x82, 0x84, 0x86, 0x88, 0x88, 0x8B, 0x8A, 0x89, 0x86, 0x88, 0x87, 0x86, 0x86, 0x83, 0x85, 0x83, 0x85, 0x84, 0x83, 0x83, 0x84, 0x86, 0x85, 0x88, 0x87, 0x89, 0x8A, 0x8C, 0x8C, 0x88, 0x88, 0x87, 0x88, 0x86, 0x86, 0x87, 0x87, 0x88, 0x87, 0x89, 0x86, 0x87, 0x87, 0x88, 0x88, 0x87, 0x88, 0x85, 0x85, 0x84, 0x87, 0x86, 0x86, 0x86, 0x85, 0x85, 0x84, 0x85, 0x82, 0x82, 0x82, 0x80, 0x7F, 0x7D, 0x7E, 0x7D, 0x7F, 0x7F, 0x80, 0x80, 0x7F, 0x7F, 0x7E, 0x81, 0x81, 0x83, 0x80, 0x80, 0x7F, 0x7D, 0x7C, 0x79, 0x79, 0x77, 0x79, 0x79, 0x7A, 0x79, 0x77, 0x76, 0x75, 0x77, 0x76, 0x76, 0x72, 0x71, 0x6E, 0x6D, 0x6D, 0x6D, 0x6E, 0x70, 0x74, 0x73, 0x73, 0x72, 0x73, 0x73, 0x74, 0x75, 0x75, 0x76, 0x77, 0x7A, 0x79, 0x7B, 0x7B, 0x80, 0x80, 0x80, 0x81, 0x81, 0x82, 0x81, 0x83, 0x82, 0x84, 0x80, 0x80, 0x80, 0x81, 0x82, 0x82, 0x83, 0x83, 0x85, 0x84, 0x86, 0x83, 0x84, 0x84, 0x86, 0x85, 0x84, 0x84, 0x83, 0x85, 0x85, 0x88, 0x88, 0x89, 0x88, 0x8A, 0x8A, 0x8B, 0x8D, 0x8D, 0x8E, 0x8C, 0x8D, 0x8B, 0x8C, 0x8C, 0x8E, 0x8D, 0x8F, 0x8F, 0x8F, 0x8F, 0x8D, 0x8E, 0x8B, 0x8C, 0x89, 0x88, 0x86, 0x85, 0x84, 0x82, 0x84, 0x84, 0x85, 0x82, 0x83, 0x82, 0x82, 0x83, 0x83, 0x84, 0x82, 0x84, 0x82, 0x83, 0x80, 0x7E, 0x7F, 0x7E, 0x7F, 0x7B, 0x7A, 0x78, 0x77, 0x75, 0x73, 0x74, 0x71, 0x70, 0x6F, 0x72, 0x73, 0x70, 0x70, 0x6D, 0x6C, 0x69, 0x6A, 0x6C, 0x6F, 0x72, 0x72, 0x75, 0x74, 0x76, 0x74, 0x75, 0x78, 0x79, 0x7B, 0x78, 0x77, 0x74, 0x75, 0x75, 0x75, 0x76, 0x75, 0x77, 0x77, 0x79, 0x79, 0x7A, 0x79, 0x7B, 0x80, 0x81, 0x83, 0x80, 0x81, 0x81, 0x81, 0x81, 0x82, 0x83, 0x82, 0x85, 0x85, 0x88, 0x89, 0x88, 0x88, 0x88, 0x8B, 0x8A, 0x8B, 0x8A, 0x8C, 0x8C, 0x8C, 0x8C, 0x8C, 0x91, 0x92, 0x94, 0x93, 0x96, 0x96, 0x96, 0x96, 0x95, 0x96, 0x94, 0x95, 0x91, 0x8F, 0x8B, 0x8A, 0x8B, 0x8F, 0x91, 0x90, 0x8F, 0x8C, 0x8E, 0x8D, 0x8F, 0x8E, 0x8E, 0x8E, 0x8C, 0x8B, 0x87, 0x85, 0x83, 0x84, 0x81, 0x80, 0x7D, 0x7C, 0x7B, 0x79, 0x77, 0x76, 0x79, 0x79, 0x78, 0x74, 0x73, 0x71, 0x6F, 0x6E, 0x6D, 0x6E, 0x6D, 0x6D, 0x6C, 0x70, 0x70, 0x72, 0x72, 0x74, 0x75, 0x74, 0x73, 0x6E, 0x6D, 0x6B, 0x6C, 0x6A, 0x6B, 0x6C, 0x70, 0x70, 0x6E, 0x6F, 0x71, 0x73, 0x72, 0x75, 0x77, 0x7B, 0x7B, 0x7B, 0x79, 0x79, 0x7B, 0x7B, 0x7B, 0x7A, 0x7D, 0x7D, 0x7E, 0x7F, 0x82, 0x85, 0x88, 0x8A, 0x89, 0x8B, 0x8C, 0x8E, 0x8E, 0x8F, 0x90, 0x8E, 0x8F, 0x8E, 0x92, 0x93, 0x95, 0x94, 0x96, 0x9B, 0x9C, 0x9D, 0x9C, 0x9F, 0x9C, 0x97, 0x95, 0x97, 0x9B, 0x9A, 0x9C, 0x9A, 0x9B, 0x98, 0x96, 0x96, 0x98, 0x9B, 0x96, 0x93, 0x8E, 0x8D, 0x8C, 0x8A, 0x8A, 0x89, 0x89, 0x85, 0x84, 0x81, 0x80, 0x80, 0x7E, 0x7E, 0x7B, 0x7B, 0x77, 0x76, 0x75, 0x73, 0x71, 0x6D, 0x70, 0x73, 0x75, 0x71, 0x6F, 0x6E, 0x6F, 0x70, 0x6D, 0x6C, 0x68, 0x68, 0x67, 0x69, 0x69, 0x65, 0x65, 0x64, 0x64, 0x5E, 0x5D, 0x62, 0x6C, 0x73, 0x72, 0x6F, 0x6D, 0x73, 0x75, 0x74, 0x6F, 0x6F, 0x70, 0x71, 0x71, 0x6F, 0x71, 0x74, 0x7D, 0x80, 0x80, 0x7C, 0x7E, 0x86, 0x8C, 0x8D, 0x87, 0x88, 0x8A, 0x8F, 0x8D, 0x8F, 0x94, 0x9B, 0xA4, 0xA4, 0x9D, 0x94, 0x97, 0x9C, 0xA1, 0x9D, 0x9C, 0x9F, 0xA4, 0xA0, 0x94, 0x92, 0x9F, 0xB6, 0xBC, 0xB5, 0xA5, 0x9A, 0x96, 0x9B, 0x9E, 0x98, 0x90, 0x8C, 0x8C, 0x83, 0x7E, 0x84, 0x95, 0x97, 0x8C, 0x7C, 0x72, 0x77, 0x81, 0x8C, 0x8C, 0x84, 0x77, 0x78, 0x7C, 0x7A, 0x72, 0x74, 0x7A, 0x74, 0x5F, 0x4E, 0x60, 0x78, 0x74, 0x5C, 0x56, 0x5B, 0x5D, 0x56, 0x50, 0x59, 0x72, 0x89, 0x81, 0x67, 0x50, 0x5A, 0x74, 0x74, 0x4D, 0x3C, 0x5A, 0x6E, 0x60, 0x59, 0x7B, 0x92, 0x7D, 0x52, 0x4A, 0x6C, 0x99, 0xB1, 0xA1, 0x81, 0x69, 0x78, 0x95, 0x86, 0x62, 0x73, 0xA4, 0xA5, 0x7A, 0x76, 0xAC, 0xCF, 0xA6, 0x6C, 0x73, 0xAB, 0xD4, 0xDA, 0xC5, 0x9A, 0x80, 0xA5, 0xC6, 0x8D, 0x5C, 0x94, 0xE1, 0xC4, 0x6B, 0x74, 0xC6, 0xC9, 0x70, 0x53, 0x93, 0xC8, 0xC2, 0xB3, 0xA2, 0x79, 0x78, 0xA6, 0x96, 0x4B, 0x48, 0xA7, 0xC0, 0x63, 0x3E, 0x85, 0xB7, 0x6D, 0x34, 0x63, 0xA9, 0x94, 0x7B, 0x83, 0x62, 0x35, 0x5E, 0x8E, 0x40, 0x10, 0x6D, 0xBB, 0x58, 0x0B, 0x55, 0xAB, 0x58, 0x09, 0x4D, 0xA4, 0x79, 0x58, 0x83, 0x61, 0x1D, 0x4B, 0x9C, 0x63, 0x26, 0x82, 0xC4, 0x69, 0x1E, 0x84, 0xBE, 0x62, 0x2C, 0x94, 0xC2, 0x86, 0x72, 0xAE, 0x82, 0x51, 0x8D, 0xC9, 0x7A, 0x56, 0xC0, 0xE2, 0x6D, 0x5B, 0xD8, 0xD1, 0x4E, 0x76, 0xFA, 0xD7, 0x94, 0xB2, 0xBD, 0x6E, 0x79, 0xC7, 0xAD, 0x58, 0x93, 0xE2, 0x9A, 0x58, 0xA9, 0xD8, 0x6E, 0x5F, 0xD9, 0xCB, 0x8D, 0x8D, 0x9F, 0x6E, 0x65, 0x93, 0x87, 0x4D, 0x69, 0xA4, 0x6A, 0x35, 0x6E, 0x8D, 0x32, 0x38, 0xA0, 0x98, 0x51, 0x60, 0x74, 0x40, 0x37, 0x68, 0x5B, 0x23, 0x52, 0x8B, 0x41, 0x1A, 0x5E, 0x71, 0x27, 0x4B, 0xA3, 0x89, 0x49, 0x69, 0x77, 0x52, 0x56, 0x7D, 0x70, 0x5E, 0x8E, 0x9F, 0x6D, 0x70, 0xA9, 0x90, 0x6B, 0xA4, 0xD8, 0xA2, 0x7E, 0xA2, 0xA3, 0x83, 0x97, 0xB7, 0x98, 0x95, 0xD2, 0xC0, 0x86, 0xA2, 0xC7, 0x8A, 0x8A, 0xE0, 0xE0, 0xA5, 0xA8, 0xBE, 0xA5, 0x92, 0xAD, 0xAA, 0x8A, 0x9E, 0xAC, 0x7E, 0x80, 0x9F, 0x7C, 0x6B, 0x9F, 0xA9, 0x7B, 0x6F, 0x7F, 0x76, 0x55, 0x60, 0x79, 0x55, 0x58, 0x7C, 0x59, 0x34, 0x5B, 0x56, 0x2B, 0x5D, 0x96, 0x67, 0x35, 0x43, 0x59, 0x3A, 0x34, 0x5F, 0x59, 0x41, 0x6F, 0x6E, 0x40, 0x5D, 0x74, 0x53, 0x71, 0xAE, 0x95, 0x63, 0x72, 0x8C, 0x7C, 0x72, 0x9A, 0x98, 0x79, 0x9F, 0xB4, 0x78, 0x8C, 0xB8, 0x85, 0x93, 0xE6, 0xDC, 0x93, 0x99, 0xB6, 0xAA, 0x9A, 0xC0, 0xC9, 0xA6, 0xC4, 0xD4, 0x9F, 0xA2, 0xC0, 0x90, 0x99, 0xD4, 0xBE, 0x96, 0x91, 0x9A, 0x90, 0x86, 0x8C, 0x8E, 0x76, 0x85, 0x8A, 0x5F, 0x61, 0x73, 0x4C, 0x5B, 0x9C, 0x81, 0x4D, 0x48, 0x5E, 0x52, 0x40, 0x50, 0x4A, 0x33, 0x58, 0x5F, 0x23, 0x34, 0x50, 0x38, 0x57, 0x96, 0x72, 0x36, 0x4C, 0x68, 0x61, 0x63, 0x78, 0x6B, 0x6E, 0x90, 0x86, 0x6D, 0x85, 0x82, 0x79, 0xB0, 0xCD, 0x96, 0x74, 0x99, 0xA0, 0x9D, 0xB5, 0xB4, 0x9A, 0xBD, 0xD5, 0xA8, 0xAA, 0xBB, 0x97, 0xAE, 0xF5, 0xD9, 0xA1, 0xAC, 0xB4, 0xA4, 0xB4, 0xAE, 0x90, 0x9F, 0xAA, 0x8C, 0x89, 0x92, 0x71, 0x73, 0xA2, 0x8E, 0x70, 0x6C, 0x60, 0x61, 0x77, 0x5F, 0x4A, 0x6C, 0x5B, 0x2E, 0x45, 0x54, 0x1B, 0x26, 0x79, 0x73, 0x31, 0x32, 0x49, 0x42, 0x3B, 0x52, 0x61, 0x50, 0x47, 0x61, 0x68, 0x5A, 0x6A, 0x8E, 0x91, 0x6E, 0x6C, 0x7E, 0x85, 0x87, 0x8E, 0x9D, 0xA7, 0xA6, 0x95, 0x8D, 0x9C, 0xBF, 0xDB, 0xDA, 0xC8, 0xBA, 0xC1, 0xCA, 0xCC, 0xC8, 0xC5, 0xC6, 0xC2, 0xB7, 0xA3, 0x98, 0x9D, 0xAE, 0xB0, 0xA4, 0x96, 0x8A, 0x87, 0x83, 0x7F, 0x77, 0x75, 0x77, 0x73, 0x63, 0x48, 0x40, 0x54, 0x6F, 0x69, 0x4D, 0x34, 0x2D, 0x35, 0x38, 0x3A, 0x3C, 0x45, 0x46, 0x3C, 0x30, 0x34, 0x4C, 0x65, 0x70, 0x64, 0x5B, 0x5F, 0x69, 0x6E, 0x74, 0x7B, 0x82, 0x88, 0x85, 0x84, 0x8C, 0xA7, 0xC2, 0xCA, 0xB9, 0xAD, 0xB3, 0xBB, 0xC4, 0xCA, 0xD1, 0xD1, 0xCC, 0xBD, 0xB6, 0xBC, 0xC8, 0xC8, 0xB9, 0xAB, 0xA2, 0xA0, 0x9A, 0x97, 0x91, 0x8D, 0x86, 0x78, 0x6B, 0x6A, 0x7A, 0x82, 0x78, 0x5E, 0x4D, 0x48, 0x48, 0x45, 0x45, 0x47, 0x3F, 0x30, 0x22, 0x2C, 0x43, 0x58, 0x51, 0x3E, 0x39, 0x44, 0x4F, 0x56, 0x5E, 0x63, 0x65, 0x62, 0x66, 0x70, 0x84, 0x95, 0x97, 0x8C, 0x8B, 0x96, 0xA0, 0xAC, 0xB3, 0xB5, 0xB3, 0xB6, 0xC1, 0xD3, 0xE7, 0xEA, 0xDB, 0xC5, 0xBD, 0xBB, 0xBD, 0xBA, 0xB3, 0xAA, 0x9F, 0x99, 0x93, 0x97, 0x98, 0x91, 0x7F, 0x70, 0x6F, 0x70, 0x6F, 0x64, 0x5B, 0x4F, 0x42, 0x3A, 0x49, 0x63, 0x5A, 0x35, 0x20, 0x2B, 0x38, 0x3A, 0x3A, 0x3C, 0x3E, 0x3D, 0x46, 0x59, 0x6F, 0x70, 0x61, 0x65, 0x73, 0x7E, 0x7E, 0x83, 0x8B, 0x90, 0x92, 0x9E, 0xBE, 0xD2, 0xC6, 0xB2, 0xBC, 0xCD, 0xD1, 0xD3, 0xD6, 0xD4, 0xC3, 0xBB, 0xC2, 0xCB, 0xBF, 0xAA, 0xA1, 0x9F, 0x9E, 0x96, 0x94, 0x8B, 0x78, 0x64, 0x6A, 0x86, 0x83, 0x6A, 0x57, 0x59, 0x54, 0x4D, 0x45, 0x34, 0x1B, 0x14, 0x36, 0x62, 0x5F, 0x33, 0x23, 0x30, 0x3F, 0x4C, 0x56, 0x58, 0x50, 0x5D, 0x79, 0x90, 0x83, 0x6E, 0x76, 0x8C, 0xA2, 0xAF, 0xB1, 0xA1, 0x9F, 0xBD, 0xEA, 0xED, 0xCC, 0xC0, 0xD7, 0xE4, 0xD8, 0xC8, 0xB0, 0xA3, 0xAB, 0xC0, 0xBA, 0xA5, 0x98, 0x96, 0x94, 0x88, 0x7A, 0x60, 0x55, 0x6F, 0x8F, 0x7C, 0x53, 0x39, 0x3C, 0x40, 0x2D, 0x1F, 0x22, 0x2D, 0x3A, 0x45, 0x37, 0x31, 0x3A, 0x47, 0x52, 0x55, 0x56, 0x5A, 0x60, 0x72, 0x92, 0x8D, 0x7D, 0x96, 0xA7, 0x98, 0x9A, 0xAC, 0xB0, 0xD8, 0xFF, 0xF8, 0xD0, 0xCF, 0xD1, 0xC9, 0xBD, 0xAC, 0xB8, 0xD8, 0xC7, 0xA3, 0x97, 0x9C, 0x94, 0x7A, 0x6E, 0x88, 0xA3, 0x84, 0x50, 0x49, 0x5F, 0x3C, 0x14, 0x2A, 0x69, 0x6F, 0x21, 0x00, 0x2D, 0x37, 0x1F, 0x33, 0x5A, 0x73, 0x55, 0x3E, 0x5D, 0x69, 0x5E, 0x6E, 0x93, 0xB0, 0x98, 0x7C, 0xA5, 0xB4, 0xA6, 0xC0, 0xF6, 0xFF, 0xD3, 0xBF, 0xD8, 0xCE, 0xB5, 0xC2, 0xD4, 0xC9, 0xAD, 0xA4, 0xA4, 0x8E, 0x86, 0x8E, 0x94, 0x83, 0x6C, 0x69, 0x5B, 0x4A, 0x4D, 0x61, 0x5C, 0x36, 0x27, 0x2B, 0x35, 0x3F, 0x52, 0x5A, 0x45, 0x38, 0x43, 0x55, 0x64, 0x7C, 0x88, 0x75, 0x64, 0x78, 0x93, 0xA7, 0xC8, 0xDD, 0xBF, 0x98, 0xAD, 0xCC, 0xD0, 0xDA, 0xE2, 0xC5, 0xA0, 0xA6, 0xB4, 0xAC, 0xAC, 0xAF, 0x96, 0x78, 0x7B, 0x82, 0x7B, 0x82, 0x7E, 0x51, 0x31, 0x3A, 0x40, 0x46, 0x5D, 0x49, 0x12, 0x14, 0x30, 0x3D, 0x54, 0x68, 0x49, 0x31, 0x4E, 0x62, 0x73, 0x93, 0x9B, 0x6D, 0x6E, 0x9C, 0xB3, 0xD0, 0xEA, 0xC3, 0x8C, 0xAE, 0xD7, 0xD7, 0xE3, 0xD6, 0xA1, 0x92, 0xB1, 0xB0, 0xAE, 0xB2, 0x92, 0x6C, 0x77, 0x82, 0x80, 0x8A, 0x68, 0x28, 0x1C, 0x42, 0x5D, 0x75, 0x4E, 0x0F, 0x10, 0x3A, 0x59, 0x78, 0x6C, 0x38, 0x3A, 0x5D, 0x82, 0xAE, 0xB3, 0x74, 0x55, 0x7B, 0xBA, 0xF8, 0xFF, 0xCE, 0x8E, 0x7F, 0xAB, 0xF0, 0xFC, 0xD9, 0xAE, 0x8C, 0x80, 0xA4, 0xC8, 0xB9, 0x9A, 0x7C, 0x5B, 0x47, 0x63, 0x80, 0x6E, 0x49, 0x2B, 0x18, 0x15, 0x2F, 0x44, 0x48, 0x45, 0x44, 0x44, 0x48, 0x57, 0x64, 0x72, 0x7C, 0x89, 0x90, 0x8E, 0x96, 0xAC, 0xCC, 0xDC, 0xDF, 0xDA, 0xD1, 0xC9, 0xC6, 0xCB, 0xC8, 0xC0, 0xB3, 0xAB, 0xA3, 0x99, 0x92, 0x88, 0x7D, 0x6D, 0x60, 0x51, 0x43, 0x39, 0x2F, 0x2A, 0x28, 0x2D, 0x2F, 0x33, 0x3C, 0x42, 0x48, 0x4F, 0x5D, 0x66, 0x73, 0x7F, 0x8A, 0x95, 0xA4, 0xB5, 0xC2, 0xCE, 0xD0, 0xCD, 0xCE, 0xD2, 0xCF, 0xC5, 0xC1, 0xB9, 0xB2, 0xA9, 0xA2, 0x98, 0x8D, 0x83, 0x74, 0x65, 0x54, 0x47, 0x38, 0x2E, 0x27, 0x23, 0x24, 0x27, 0x2E, 0x34, 0x3E, 0x45, 0x4F, 0x59, 0x64, 0x71, 0x7F, 0x8E, 0x9A, 0xA9, 0xB9, 0xCA, 0xD4, 0xDA, 0xD8, 0xD3, 0xCE, 0xC8, 0xC4, 0xBD, 0xB6, 0xAB, 0xA4, 0x9B, 0x92, 0x86, 0x76, 0x61, 0x4D, 0x3D, 0x2F, 0x28, 0x21, 0x1E, 0x1E, 0x25, 0x2D, 0x36, 0x40, 0x47, 0x52, 0x5C, 0x6A, 0x77, 0x8A, 0x99, 0xAB, 0xBC, 0xCB, 0xD7, 0xDE, 0xE0, 0xDA, 0xD6, 0xCE, 0xC8, 0xBF, 0xB8, 0xAE, 0xA5, 0x99, 0x8C, 0x7E, 0x6C, 0x59, 0x42, 0x2F, 0x20, 0x1C, 0x19, 0x1D, 0x23, 0x2B, 0x34, 0x3E, 0x49, 0x52, 0x5F, 0x69, 0x7A, 0x8D, 0xA1, 0xB2, 0xC5, 0xD5, 0xDF, 0xE4, 0xE1, 0xDD, 0xD3, 0xCC, 0xC5, 0xBE, 0xB4, 0xA8, 0x9A, 0x88, 0x78, 0x68, 0x58, 0x3F, 0x2C, 0x20, 0x1A, 0x1B, 0x21, 0x2A, 0x30, 0x3A, 0x45, 0x51, 0x5E, 0x6B, 0x7A, 0x88, 0x9E, 0xB3, 0xC5, 0xD2, 0xDC, 0xE0, 0xDD, 0xDD, 0xD6, 0xCE, 0xC3, 0xBB, 0xB1, 0xA2, 0x95, 0x84, 0x73, 0x61, 0x4D, 0x30, 0x1D, 0x1B, 0x1C, 0x24, 0x2D, 0x33, 0x39, 0x47, 0x52, 0x57, 0x65, 0x7C, 0x94, 0xA4, 0xB4, 0xBE, 0xC6, 0xDE, 0xF3, 0xEE, 0xD6, 0xC9, 0xC0, 0xBD, 0xBE, 0xB2, 0x99, 0x7D, 0x6A, 0x5E, 0x5E, 0x45, 0x1A, 0x0C, 0x16, 0x25, 0x36, 0x40, 0x3B, 0x43, 0x51, 0x60, 0x7B, 0x91, 0x93, 0x9A, 0xB5, 0xCF, 0xEE, 0xF3, 0xDD, 0xD2, 0xCE, 0xCA, 0xC4, 0xB8, 0xA0, 0x91, 0x84, 0x79, 0x6D, 0x4A, 0x25, 0x19, 0x1F, 0x2C, 0x36, 0x2F, 0x32, 0x41, 0x50, 0x66, 0x6F, 0x71, 0x7E, 0x98, 0xBB, 0xDD, 0xD7, 0xCB, 0xD2, 0xDA, 0xDE, 0xCF, 0xBB, 0xAD, 0xA6, 0x9E, 0x91, 0x6E, 0x4D, 0x3D, 0x39, 0x38, 0x26, 0x1A, 0x23, 0x36, 0x46, 0x51, 0x50, 0x5B, 0x72, 0x8F, 0xA8, 0xAE, 0xB8, 0xCF, 0xE4, 0xE9, 0xDD, 0xCB, 0xC5, 0xC0, 0xB6, 0xA6, 0x8C, 0x77, 0x66, 0x57, 0x3E, 0x22, 0x15, 0x1E, 0x2C, 0x3A, 0x3E, 0x41, 0x4F, 0x63, 0x7E, 0x8E, 0x96, 0xAA, 0xCE, 0xE9, 0xEC, 0xDB, 0xD1, 0xCF, 0xCB, 0xC0, 0xA6, 0x91, 0x84, 0x7C, 0x62, 0x35, 0x19, 0x19, 0x25, 0x2B, 0x2C, 0x31, 0x42, 0x56, 0x67, 0x72, 0x7E, 0x9D, 0xC1, 0xDF, 0xE2, 0xDD, 0xDB, 0xD7, 0xD2, 0xC1, 0xB0, 0x9E, 0x91, 0x7F, 0x5D, 0x38, 0x22, 0x1F, 0x25, 0x27, 0x26, 0x32, 0x43, 0x57, 0x67, 0x72, 0x8A, 0xAA, 0xCE, 0xDF, 0xDD, 0xDE, 0xDB, 0xD5, 0xC6, 0xB6, 0xA5, 0x97, 0x85, 0x69, 0x44, 0x28, 0x1E, 0x1F, 0x26, 0x28, 0x31, 0x41, 0x52, 0x68, 0x74, 0x89, 0xAA, 0xCE, 0xE3, 0xE3, 0xDC, 0xD8, 0xD2, 0xC4, 0xB4, 0x9F, 0x8E, 0x77, 0x5B, 0x3C, 0x1E, 0x16, 0x1D, 0x2C, 0x34, 0x3B, 0x49, 0x62, 0x7F, 0x96, 0xA8, 0xC2, 0xDF, 0xE7, 0xE2, 0xD0, 0xBB, 0xA3, 0x8E, 0x86, 0x7F, 0x77, 0x71, 0x6F, 0x6A, 0x68, 0x66, 0x65, 0x65, 0x64, 0x67, 0x66, 0x69, 0x69, 0x6D, 0x6E, 0x71, 0x72, 0x74, 0x77, 0x78, 0x7B, 0x7B, 0x7E, 0x7E, 0x81, 0x80, 0x82, 0x83, 0x84, 0x85, 0x85, 0x87, 0x86, 0x88, 0x87, 0x89, 0x88, 0x88, 0x88, 0x88, 0x87, 0x86, 0x87, 0x85, 0x86, 0x83, 0x84, 0x83, 0x84, 0x83, 0x83, 0x83, 0x83, 0x84, 0x82, 0x83, 0x82, 0x82, 0x80, 0x82, 0x80, 0x80, 0x7F, 0x7F, 0x7E, 0x7D, 0x7D, 0x7B, 0x7C, 0x7A, 0x7B, 0x7A, 0x7C, 0x7B, 0x7C, 0x7C, 0x7C, 0x7D, 0x7D, 0x7E, 0x7C, 0x7D, 0x7C, 0x7D, 0x7C, 0x7C, 0x7C, 0x7A, 0x7B, 0x7A, 0x7B, 0x7A, 0x7B, 0x7B, 0x7B, 0x7C, 0x7C, 0x7E, 0x7C, 0x7E, 0x7D, 0x7E, 0x7E, 0x7E, 0x7F, 0x7D, 0x7F, 0x7D, 0x7E, 0x7E, 0x7E, 0x80, 0x7E, 0x80, 0x7F, 0x81, 0x80, 0x80, 0x80, 0x7F, 0x80, 0x7E, 0x80, 0x7F, 0x80, 0x80, 0x7F, 0x80, 0x7F, 0x81, 0x80, 0x82, 0x82, 0x83, 0x84, 0x83, 0x84, 0x82, 0x83, 0x81, 0x82, 0x82, 0x82, 0x82, 0x81, 0x82, 0x80, 0x82, 0x81, 0x82, 0x82, 0x82, 0x83, 0x82, 0x83, 0x82, 0x83, 0x82, 0x83, 0x83, 0x82, 0x83, 0x81, 0x82, 0x81, 0x82, 0x81, 0x82, 0x82, 0x81, 0x82, 0x80, 0x81, 0x80, 0x81, 0x80, 0x81, 0x81, 0x80, 0x81, 0x80, 0x81, 0x80, 0x81, 0x80, 0x81, 0x81, 0x81, 0x81, 0x80, 0x81, 0x7F, 0x81, 0x7F, 0x80, 0x80, 0x81, 0x81, 0x80, 0x81, 0x7F, 0x80, 0x7E, 0x80, 0x7E, 0x80, 0x7F, 0x80, 0x80, 0x80, 0x80, 0x7F, 0x80, 0x7F, 0x80, 0x7F, 0x81, 0x80, 0x80, 0x80, 0x7F, 0x81, 0x7F, 0x80, 0x7E, 0x7F, 0x7E, 0x7F, 0x7F, 0x7F, 0x80, 0x7F, 0x80, 0x7F, 0x80, 0x7E, 0x80, 0x7E, 0x80, 0x7F, 0x80, 0x80, 0x7F, 0x80, 0x7E, 0x80, 0x7E, 0x80, 0x7F, 0x81, 0x80, 0x81, 0x80, 0x80, 0x80, 0x7F, 0x81, 0x80, 0x82, 0x80, 0x81, 0x80, 0x81, 0x81, 0x80, 0x81, 0x80, 0x81, 0x80, 0x82, 0x80, 0x81, 0x81, 0x81, 0x81, 0x80, 0x81, 0x80, 0x82, 0x81, 0x81, 0x81, 0x80, 0x82, 0x80, 0x82, 0x80, 0x81, 0x81, 0x81, 0x82, 0x81, 0x82, 0x80, 0x81, 0x80, 0x81, 0x81, 0x80, 0x82, 0x80, 0x82, 0x80, 0x81, 0x80, 0x81, 0x81, 0x80, 0x81, 0x80, 0x81, 0x80, 0x81, 0x81, 0x00]
@@ -134,59 +836,1271 @@ mod test {
         assert!(is_all_zeroes);
     }
 
-    #[test]
-    pub fn test_fill_any() {
-        let file_path = "test_fill_any.gif";
-        write_data_into_file(file_path, &gif_vec());
+    fn check_fill_wav_spec<S>(file_path: &str, bits_per_sample: u16, sample_format: SampleFormat, sample: S)
+        where S: hound::Sample + Copy + Default + PartialEq {
 
-        // check that the data is valid
-        let at_least_one_not_zero = {
-            let mut buf = Vec::new();
-            File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
-            buf.into_iter().any(|d| d != 0)
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample,
+            sample_format,
         };
 
-        fill_any(file_path).unwrap();
+        {
+            let mut writer = WavWriter::create(file_path, spec).unwrap();
 
-        // check that the file does have all samples filled with zeroes
-        let is_all_zeroes = {
-            let mut buf = Vec::new();
-            File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
-            buf.into_iter().all(|d| d == 0)
+            for _ in 0..64 {
+                writer.write_sample(sample).unwrap();
+            }
+
+            writer.finalize().unwrap();
+        }
+
+        fill_wav(file_path).unwrap();
+
+        // check that the spec and length are kept and all samples are zeroes
+        let (filled_spec, len, is_all_zeroes) = {
+            let mut reader = WavReader::open(file_path).unwrap();
+            let is_all_zeroes = reader.samples::<S>().all(|s| s.unwrap() == S::default());
+            (reader.spec(), reader.len(), is_all_zeroes)
         };
 
         fs::remove_file(file_path).unwrap();
 
-        assert!(at_least_one_not_zero);
+        assert_eq!(spec, filled_spec);
+        assert_eq!(64, len);
         assert!(is_all_zeroes);
     }
 
     #[test]
-    pub fn test_c_zero_fill_matching() {
-        let file_path = "test_c_zero_fill_matching.WAV";
-        write_data_into_file(file_path, &wav_vec());
+    pub fn test_fill_wav_int_8() {
+        check_fill_wav_spec("test_fill_wav_int_8.wav", 8, SampleFormat::Int, 0x55i8);
+    }
 
-        // check that the samples are valid
-        let at_least_one_not_zero = {
-            let mut reader = WavReader::open(file_path).unwrap();
-            reader.samples::<i16>().any(|s| s.unwrap() != 0)
-        };
+    #[test]
+    pub fn test_fill_wav_int_16() {
+        check_fill_wav_spec("test_fill_wav_int_16.wav", 16, SampleFormat::Int, 0x1234i16);
+    }
 
-        let file_path_with_nul = &format!("{}\0", file_path);
-        let c_file_path = CStr::from_bytes_with_nul(file_path_with_nul.as_bytes()).unwrap();
-        let status = zero_fill_matching(c_file_path.as_ptr());
+    #[test]
+    pub fn test_fill_wav_int_24() {
+        check_fill_wav_spec("test_fill_wav_int_24.wav", 24, SampleFormat::Int, 0x12_3456i32);
+    }
 
-        assert!(status == ZERO_FILL_OK);
+    #[test]
+    pub fn test_fill_wav_int_32() {
+        check_fill_wav_spec("test_fill_wav_int_32.wav", 32, SampleFormat::Int, 0x1234_5678i32);
+    }
 
-        // check that the file does have all samples filled with zeroes
-        let is_all_zeroes = {
-            let mut reader = WavReader::open(file_path).unwrap();
-            reader.samples::<i16>().all(|s| s.unwrap() == 0)
-        };
+    #[test]
+    pub fn test_fill_wav_float_32() {
+        check_fill_wav_spec("test_fill_wav_float_32.wav", 32, SampleFormat::Float, 0.5f32);
+    }
 
-        fs::remove_file(file_path).unwrap();
+    fn riff_vec(chunks: &[(&[u8], &[u8])]) -> Vec<u8> {
+        let mut body = b"WAVE".to_vec();
 
-        assert!(at_least_one_not_zero);
-        assert!(is_all_zeroes);
+        for &(id, payload) in chunks {
+            body.extend_from_slice(id);
+            body.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            body.extend_from_slice(payload);
+
+            if payload.len() % 2 == 1 {
+                body.push(0);
+            }
+        }
+
+        let mut riff = b"RIFF".to_vec();
+        riff.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        riff.extend_from_slice(&body);
+        riff
+    }
+
+    fn pcm_16_mono_fmt() -> Vec<u8> {
+        vec![0x01, 0x00, 0x01, 0x00, 0x40, 0x1F, 0x00, 0x00, 0x80, 0x3E, 0x00, 0x00, 0x02, 0x00, 0x10, 0x00]
+    }
+
+    #[test]
+    pub fn test_fill_wav_keeps_other_chunks() {
+        let file_path = "test_fill_wav_keeps_other_chunks.wav";
+        let samples = [0x11u8, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88];
+
+        let data = riff_vec(&[
+            (b"bext", b"desc"),
+            (b"fmt ", &pcm_16_mono_fmt()),
+            (b"LIST", b"INFOINAM\x04\x00\x00\x00name"),
+            (b"data", &samples),
+            (b"cue ", b"\x00\x00\x00\x00"),
+        ]);
+
+        write_data_into_file(file_path, &data);
+        fill_wav(file_path).unwrap();
+
+        let mut filled = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut filled).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        // only the data payload may differ
+        let data_start = data.windows(4).position(|w| w == b"data").unwrap() + 8;
+        let data_end = data_start + samples.len();
+
+        assert_eq!(data.len(), filled.len());
+        assert_eq!(&data[..data_start], &filled[..data_start]);
+        assert!(filled[data_start..data_end].iter().all(|&b| b == 0));
+        assert_eq!(&data[data_end..], &filled[data_end..]);
+    }
+
+    #[test]
+    pub fn test_fill_wav_in_place() {
+        let file_path = "test_fill_wav_in_place.anyext";
+        let samples = [0x11u8, 0x22, 0x33, 0x44];
+
+        let data = riff_vec(&[
+            (b"fmt ", &pcm_16_mono_fmt()),
+            (b"data", &samples),
+            (b"LIST", b"INFOINAM\x04\x00\x00\x00name"),
+        ]);
+
+        write_data_into_file(file_path, &data);
+
+        let options = FillOptions {
+            mode: Mode::InPlace,
+            ..FillOptions::default()
+        };
+
+        fill_wav_with(file_path, &options).unwrap();
+
+        let mut filled = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut filled).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        let data_start = data.windows(4).position(|w| w == b"data").unwrap() + 8;
+        let data_end = data_start + samples.len();
+
+        assert_eq!(data.len(), filled.len());
+        assert_eq!(&data[..data_start], &filled[..data_start]);
+        assert!(filled[data_start..data_end].iter().all(|&b| b == 0));
+        assert_eq!(&data[data_end..], &filled[data_end..]);
+    }
+
+    /// A mono 8000 Hz `fmt ` chunk for 8-bit samples, with the cbSize field that G.711 files carry.
+    fn mono_8_bit_fmt(format_tag: u8) -> Vec<u8> {
+        vec![format_tag, 0x00, 0x01, 0x00, 0x40, 0x1F, 0x00, 0x00, 0x40, 0x1F, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x00, 0x00]
+    }
+
+    #[test]
+    pub fn test_fill_wav_encoding_silence() {
+        let file_path = "test_fill_wav_encoding_silence.wav";
+        let samples = [0x11u8, 0x22, 0x33, 0x44, 0x55];
+
+        let options = FillOptions {
+            verify: true,
+            ..FillOptions::default()
+        };
+
+        for &(format_tag, name, silence) in &[(1, "integer", 0x80), (6, "A-law", 0xD5), (7, "mu-law", 0xFF)] {
+            let data = riff_vec(&[(b"fmt ", &mono_8_bit_fmt(format_tag)), (b"data", &samples), (b"LIST", b"INFO")]);
+            write_data_into_file(file_path, &data);
+
+            let plan = plan_wav(file_path).unwrap();
+            fill_wav_with(file_path, &options).unwrap();
+
+            let mut filled = Vec::new();
+            File::open(file_path).unwrap().read_to_end(&mut filled).unwrap();
+            fs::remove_file(file_path).unwrap();
+
+            let data_start = data.windows(4).position(|w| w == b"data").unwrap() + 8;
+            let data_end = data_start + samples.len();
+
+            assert_eq!(Some(format!("1 channel 8-bit {} WAV at 8000 Hz with 5 samples", name)), plan.structure);
+            assert_eq!(vec![Region { range: data_start as u64..data_end as u64, fill: silence }], plan.regions);
+            assert_eq!(&data[..data_start], &filled[..data_start]);
+            assert!(filled[data_start..data_end].iter().all(|&b| b == silence));
+            assert_eq!(&data[data_end..], &filled[data_end..]);
+            assert_eq!(filled, fill_wav_buffer(&data).unwrap());
+        }
+    }
+
+    #[test]
+    pub fn test_fill_wav_encoding_unsupported() {
+        // G.721 ADPCM has no single byte that encodes silence
+        let mut fmt = mono_8_bit_fmt(0x40);
+        fmt[14] = 4;
+        let data = riff_vec(&[(b"fmt ", &fmt), (b"data", &[0x11, 0x22])]);
+
+        assert_eq!(ZERO_FILL_UNSUPPORTED, status_code(&fill_wav_buffer(&data).unwrap_err()));
+    }
+
+    /// A WAVE_FORMAT_EXTENSIBLE `fmt ` chunk at 48000 Hz, whose sub-format GUID is built from
+    /// `format_tag` and `guid_suffix`.
+    fn extensible_fmt(channels: u16, bits: u16, valid_bits: u16, channel_mask: u32, format_tag: u16, guid_suffix: &[u8; 14])
+        -> Vec<u8> {
+
+        let block_align = channels * bits / 8;
+
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&0xFFFEu16.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&48000u32.to_le_bytes());
+        fmt.extend_from_slice(&(48000 * u32::from(block_align)).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+        fmt.extend_from_slice(&22u16.to_le_bytes());
+        fmt.extend_from_slice(&valid_bits.to_le_bytes());
+        fmt.extend_from_slice(&channel_mask.to_le_bytes());
+        fmt.extend_from_slice(&format_tag.to_le_bytes());
+        fmt.extend_from_slice(guid_suffix);
+        fmt
+    }
+
+    const KSDATAFORMAT_SUFFIX: &[u8; 14] = b"\x00\x00\x00\x00\x10\x00\x80\x00\x00\xAA\x00\x38\x9B\x71";
+    const AMBISONIC_SUFFIX: &[u8; 14] = b"\x00\x00\x21\x07\xD3\x11\x86\x44\xC8\xC1\xCA\x00\x00\x00";
+
+    #[test]
+    pub fn test_fill_wav_extensible() {
+        let file_path = "test_fill_wav_extensible.wav";
+        let samples: Vec<u8> = (1..=48).collect();
+
+        let fmts = [
+            // 5.1 float
+            extensible_fmt(6, 32, 32, 0x3F, 3, KSDATAFORMAT_SUFFIX),
+            // 24-bit samples in 32-bit containers
+            extensible_fmt(2, 32, 24, 0x3, 1, KSDATAFORMAT_SUFFIX),
+            // first order ambisonics, which has no speaker positions
+            extensible_fmt(4, 16, 16, 0, 1, AMBISONIC_SUFFIX),
+        ];
+
+        for &mode in &[Mode::Rewrite, Mode::InPlace] {
+            let options = FillOptions {
+                mode,
+                verify: true,
+                ..FillOptions::default()
+            };
+
+            for fmt in &fmts {
+                let data = riff_vec(&[(b"fmt ", fmt), (b"data", &samples), (b"LIST", b"INFO")]);
+                write_data_into_file(file_path, &data);
+
+                fill_wav_with(file_path, &options).unwrap();
+
+                let mut filled = Vec::new();
+                File::open(file_path).unwrap().read_to_end(&mut filled).unwrap();
+                fs::remove_file(file_path).unwrap();
+
+                let data_start = data.windows(4).position(|w| w == b"data").unwrap() + 8;
+                let data_end = data_start + samples.len();
+
+                // the fmt chunk, with its channel mask and sub-format, is kept byte for byte
+                assert_eq!(&data[..data_start], &filled[..data_start]);
+                assert!(filled[data_start..data_end].iter().all(|&b| b == 0));
+                assert_eq!(&data[data_end..], &filled[data_end..]);
+                assert_eq!(filled, fill_wav_buffer(&data).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_plan_wav_extensible() {
+        let file_path = "test_plan_wav_extensible.wav";
+        let data = riff_vec(&[(b"fmt ", &extensible_fmt(2, 32, 24, 0x3, 1, KSDATAFORMAT_SUFFIX)), (b"data", &[0x11; 16])]);
+        write_data_into_file(file_path, &data);
+
+        let plan = plan_wav(file_path).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(Some("2 channel 32-bit integer WAV (sub-format 00000001-0000-0010-8000-00AA00389B71, \
+            24 valid bits, channel mask 0x3) at 48000 Hz with 4 samples".to_owned()), plan.structure);
+    }
+
+    #[test]
+    pub fn test_fill_wav_extensible_unsupported() {
+        let fmt = extensible_fmt(2, 16, 16, 0x3, 1, b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+        let data = riff_vec(&[(b"fmt ", &fmt), (b"data", &[0x11; 8])]);
+
+        let e = fill_wav_buffer(&data).unwrap_err();
+
+        assert_eq!(ZERO_FILL_UNSUPPORTED, status_code(&e));
+        assert_eq!("unsupported format: WAV sub-format 00000001-0000-0000-0000-000000000000", e.kind().to_string());
+
+        // the extensible fields are required
+        let data = riff_vec(&[(b"fmt ", &fmt[..24]), (b"data", &[0x11; 8])]);
+        assert_eq!(ZERO_FILL_FORMAT, status_code(&fill_wav_buffer(&data).unwrap_err()));
+    }
+
+    /// The header of an RF64 or BW64 file with 16-bit mono samples, up to the `data` chunk header,
+    /// which defers its size of `data_len` to the `ds64` chunk.
+    fn ds64_header(container: &[u8; 4], data_len: u64) -> Vec<u8> {
+        let fmt = pcm_16_mono_fmt();
+        let header_len = 12 + 8 + 28 + 8 + fmt.len() as u64 + 8;
+
+        let mut header = container.to_vec();
+        header.extend_from_slice(b"\xFF\xFF\xFF\xFFWAVEds64\x1C\x00\x00\x00");
+        header.extend_from_slice(&(header_len - 8 + data_len).to_le_bytes());
+        header.extend_from_slice(&data_len.to_le_bytes());
+        header.extend_from_slice(&(data_len / 2).to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(b"fmt \x10\x00\x00\x00");
+        header.extend_from_slice(&fmt);
+        header.extend_from_slice(b"data\xFF\xFF\xFF\xFF");
+        header
+    }
+
+    #[test]
+    pub fn test_fill_wav_ds64() {
+        let file_path = "test_fill_wav_ds64.wav";
+        let samples = [0x11u8, 0x22, 0x33, 0x44];
+
+        let options = FillOptions {
+            verify: true,
+            ..FillOptions::default()
+        };
+
+        for container in &[b"RF64", b"BW64"] {
+            let mut data = ds64_header(container, samples.len() as u64);
+            let data_start = data.len();
+            data.extend_from_slice(&samples);
+
+            write_data_into_file(file_path, &data);
+
+            let plan = plan::plan(file_path, &FillOptions::default()).unwrap();
+            fill_matching_with(file_path, &options).unwrap();
+
+            let mut filled = Vec::new();
+            File::open(file_path).unwrap().read_to_end(&mut filled).unwrap();
+            fs::remove_file(file_path).unwrap();
+
+            let structure = format!("1 channel 16-bit integer {} WAV at 8000 Hz with 2 samples",
+                String::from_utf8_lossy(&container[..]));
+
+            assert_eq!("wav", plan.filler);
+            assert_eq!(Some(structure), plan.structure);
+            assert_eq!(&data[..data_start], &filled[..data_start]);
+            assert_eq!(&[0u8; 4], &filled[data_start..]);
+        }
+    }
+
+    #[test]
+    #[ignore = "writes 4 GiB of zeroes into the working directory"]
+    pub fn test_fill_wav_ds64_sparse_multi_gb() {
+        let file_path = "test_fill_wav_ds64_sparse_multi_gb.wav";
+        let data_len = (4u64 << 30) + 2;
+        let header = ds64_header(b"RF64", data_len);
+        let len = header.len() as u64 + data_len;
+
+        {
+            let mut file = File::create(file_path).unwrap();
+            file.write_all(&header).unwrap();
+            file.write_all(b"\x11\x22").unwrap();
+            file.set_len(len).unwrap();
+            file.seek(SeekFrom::End(-2)).unwrap();
+            file.write_all(b"\x33\x44").unwrap();
+        }
+
+        let options = FillOptions {
+            mode: Mode::InPlace,
+            ..FillOptions::default()
+        };
+
+        fill_wav_with(file_path, &options).unwrap();
+
+        let (filled_len, filled_header, first, last) = {
+            let mut file = File::open(file_path).unwrap();
+            let mut filled_header = vec![0u8; header.len()];
+            let (mut first, mut last) = ([0xFFu8; 2], [0xFFu8; 2]);
+
+            file.read_exact(&mut filled_header).unwrap();
+            file.read_exact(&mut first).unwrap();
+            file.seek(SeekFrom::End(-2)).unwrap();
+            file.read_exact(&mut last).unwrap();
+
+            (file.metadata().unwrap().len(), filled_header, first, last)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(len, filled_len);
+        assert_eq!(header, filled_header);
+        assert_eq!([0u8; 2], first);
+        assert_eq!([0u8; 2], last);
+        assert!(LARGEST_ALLOC.load(Ordering::SeqCst) < (1 << 30));
+    }
+
+    #[test]
+    pub fn test_redact_wav_bounded_memory() {
+        let file_path = "test_redact_wav_bounded_memory.wav";
+        let data_len = 32u64 << 20;
+
+        let mut header = b"RIFF".to_vec();
+        header.extend_from_slice(&(36 + data_len as u32).to_le_bytes());
+        header.extend_from_slice(b"WAVEfmt \x10\x00\x00\x00");
+        header.extend_from_slice(&pcm_16_mono_fmt());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&(data_len as u32).to_le_bytes());
+        let len = header.len() as u64 + data_len;
+
+        {
+            let mut file = File::create(file_path).unwrap();
+            file.write_all(&header).unwrap();
+            file.write_all(b"\x11\x22").unwrap();
+            file.set_len(len).unwrap();
+            file.seek(SeekFrom::End(-2)).unwrap();
+            file.write_all(b"\x33\x44").unwrap();
+        }
+
+        let options = FillOptions {
+            buffer_size: 4096,
+            verify: true,
+            ..FillOptions::default()
+        };
+
+        // keeps all but the first frame, far more than a rewrite may hold in memory
+        redact::redact_wav_with(file_path, &[redact::TimeRange::Frames(0, 1)], &options).unwrap();
+
+        let (filled_len, filled_header, first, last) = {
+            let mut file = File::open(file_path).unwrap();
+            let mut filled_header = vec![0u8; header.len()];
+            let (mut first, mut last) = ([0xFFu8; 2], [0xFFu8; 2]);
+
+            file.read_exact(&mut filled_header).unwrap();
+            file.read_exact(&mut first).unwrap();
+            file.seek(SeekFrom::End(-2)).unwrap();
+            file.read_exact(&mut last).unwrap();
+
+            (file.metadata().unwrap().len(), filled_header, first, last)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(len, filled_len);
+        assert_eq!(header, filled_header);
+        assert_eq!([0u8; 2], first);
+        assert_eq!([0x33u8, 0x44], last);
+        assert!(LARGEST_ALLOC.load(Ordering::SeqCst) < (data_len / 4) as usize);
+    }
+
+    /// A 16-bit mono WAV file with 8 bytes of samples, as left by a recorder that wrote `riff_len`
+    /// and `data_len` as its sizes.
+    fn unfinished_wav_vec(riff_len: u32, data_len: u32) -> Vec<u8> {
+        let mut data = riff_vec(&[(b"fmt ", &pcm_16_mono_fmt()), (b"data", &[0x11; 8])]);
+        data[4..8].copy_from_slice(&riff_len.to_le_bytes());
+        data[40..44].copy_from_slice(&data_len.to_le_bytes());
+        data
+    }
+
+    #[test]
+    pub fn test_fill_wav_recovery() {
+        let file_path = "test_fill_wav_recovery.wav";
+
+        let fill = |data: &[u8], recovery, mode| {
+            let options = FillOptions {
+                mode,
+                verify: true,
+                recovery,
+                ..FillOptions::default()
+            };
+
+            fill_file(file_path, data, |file_path| fill_wav_with(file_path, &options))
+        };
+
+        for &(riff_len, data_len) in &[(0, 0), (0xFFFF_FFFF, 0xFFFF_FFFF), (44, 4000)] {
+            let data = unfinished_wav_vec(riff_len, data_len);
+
+            let (res, unchanged) = fill(&data, Recovery::Strict, Mode::Rewrite);
+            assert_eq!(ZERO_FILL_FORMAT, status_code(&res.unwrap_err()));
+            assert_eq!(data, unchanged);
+
+            // the samples are filled, and the sizes are left as they were
+            let (res, filled) = fill(&data, Recovery::Lenient, Mode::InPlace);
+            res.unwrap();
+            assert_eq!(&data[..44], &filled[..44]);
+            assert_eq!(&[0u8; 8], &filled[44..]);
+
+            for &mode in &[Mode::Rewrite, Mode::InPlace] {
+                let (res, repaired) = fill(&data, Recovery::Repair, mode);
+                res.unwrap();
+                assert_eq!(unfinished_wav_vec(44, 8)[..44], repaired[..44]);
+                assert_eq!(&[0u8; 8], &repaired[44..]);
+
+                let mut cursor = Cursor::new(data.clone());
+                fill_wav_stream(&mut cursor, &FillOptions { recovery: Recovery::Repair, ..FillOptions::default() }).unwrap();
+                assert_eq!(repaired, cursor.into_inner());
+            }
+        }
+
+        // an empty data chunk that is valid is left as it is
+        let data = riff_vec(&[(b"fmt ", &pcm_16_mono_fmt()), (b"data", &[]), (b"LIST", b"INFO")]);
+        let (res, filled) = fill(&data, Recovery::Repair, Mode::Rewrite);
+        res.unwrap();
+        assert_eq!(data, filled);
+    }
+
+    #[test]
+    pub fn test_fill_wav_recovery_ds64() {
+        let mut data = ds64_header(b"RF64", 0);
+        data.extend_from_slice(&[0x11; 6]);
+
+        let options = FillOptions {
+            recovery: Recovery::Repair,
+            ..FillOptions::default()
+        };
+
+        let mut cursor = Cursor::new(data.clone());
+        fill_wav_stream(&mut cursor, &options).unwrap();
+
+        let mut repaired = ds64_header(b"RF64", 6);
+        repaired.extend_from_slice(&[0; 6]);
+
+        assert_eq!(repaired, cursor.into_inner());
+    }
+
+    #[test]
+    pub fn test_c_zero_fill_recovery() {
+        let file_path = "test_c_zero_fill_recovery.wav";
+        write_data_into_file(file_path, &unfinished_wav_vec(0, 0));
+
+        let options = CFillOptions {
+            recovery: Recovery::Repair,
+            ..zero_fill_default_options()
+        };
+
+        let c_file_path = CString::new(file_path).unwrap();
+        let strict_status = unsafe { zero_fill_with_options(c_file_path.as_ptr(), ptr::null()) };
+        let status = unsafe { zero_fill_with_options(c_file_path.as_ptr(), &options) };
+
+        let is_all_silence = {
+            let mut reader = WavReader::open(file_path).unwrap();
+            reader.samples::<i16>().all(|s| s.unwrap() == 0)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(ZERO_FILL_FORMAT, strict_status);
+        assert_eq!(ZERO_FILL_OK, status);
+        assert!(is_all_silence);
+    }
+
+    #[test]
+    pub fn test_c_zero_fill_wav_redact() {
+        let file_path = "test_c_zero_fill_wav_redact.wav";
+        let original = wav_vec();
+        write_data_into_file(file_path, &original);
+
+        // 8-bit mono at 11025 Hz, so 0.1 s to 0.2 s covers frames 1102 to 2205
+        let ranges = [
+            CTimeRange { unit: TimeUnit::Seconds, start: 0.1, end: 0.2 },
+            CTimeRange { unit: TimeUnit::Frames, start: 3000.0, end: 3001.0 },
+        ];
+
+        let fractional = [CTimeRange { unit: TimeUnit::Frames, start: 0.5, end: 1.0 }];
+
+        let c_file_path = CString::new(file_path).unwrap();
+        let null_status = unsafe { zero_fill_wav_redact(c_file_path.as_ptr(), ptr::null(), 1, ptr::null()) };
+        let fractional_status = unsafe { zero_fill_wav_redact(c_file_path.as_ptr(), fractional.as_ptr(), 1, ptr::null()) };
+        let status = unsafe { zero_fill_wav_redact(c_file_path.as_ptr(), ranges.as_ptr(), ranges.len(), ptr::null()) };
+
+        let mut filled = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut filled).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        let mut expected = original;
+        expected[44 + 1102..44 + 2205].iter_mut().for_each(|b| *b = 0x80);
+        expected[44 + 3000] = 0x80;
+
+        assert_eq!(ZERO_FILL_NULL_POINTER, null_status);
+        assert_eq!(ZERO_FILL_INVALID_ARGUMENT, fractional_status);
+        assert_eq!(ZERO_FILL_OK, status);
+        assert_eq!(expected, filled);
+    }
+
+    #[test]
+    pub fn test_fill_wav_in_place_rejects_unsupported() {
+        let file_path = "test_fill_wav_in_place_rejects_unsupported.wav";
+
+        // 12-bit samples cannot be zero filled
+        let mut fmt = pcm_16_mono_fmt();
+        fmt[14] = 12;
+        let data = riff_vec(&[(b"fmt ", &fmt), (b"data", &[0x11, 0x22])]);
+
+        write_data_into_file(file_path, &data);
+
+        let options = FillOptions {
+            mode: Mode::InPlace,
+            ..FillOptions::default()
+        };
+
+        let res = fill_wav_with(file_path, &options);
+
+        let mut unchanged = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut unchanged).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert!(res.is_err());
+        assert_eq!(data, unchanged);
+    }
+
+    #[test]
+    pub fn test_fill_matching_sniffs_wav() {
+        let file_path = "test_fill_matching_sniffs_wav.anyext";
+        write_data_into_file(file_path, &wav_vec());
+
+        fill_matching(file_path).unwrap();
+
+        // the header must be intact for the samples to be read back
+        let is_all_zeroes = {
+            let mut reader = WavReader::open(file_path).unwrap();
+            reader.samples::<i16>().all(|s| s.unwrap() == 0)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert!(is_all_zeroes);
+    }
+
+    #[test]
+    pub fn test_fill_matching_forced_extension() {
+        let file_path = "test_fill_matching_forced_extension.anyext";
+        write_data_into_file(file_path, &wav_vec());
+
+        let options = FillOptions {
+            detection: Detection::Extension,
+            ..FillOptions::default()
+        };
+
+        fill_matching_with(file_path, &options).unwrap();
+
+        let mut buf = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert!(buf.into_iter().all(|d| d == 0));
+    }
+
+    #[test]
+    pub fn test_plan_wav() {
+        let file_path = "test_plan_wav.anyext";
+        let data = riff_vec(&[
+            (b"fmt ", &pcm_16_mono_fmt()),
+            (b"data", &[0x11, 0x22, 0x33, 0x44]),
+            (b"LIST", b"INFO"),
+        ]);
+
+        write_data_into_file(file_path, &data);
+
+        let plan = plan::plan(file_path, &FillOptions::default()).unwrap();
+
+        let mut unchanged = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut unchanged).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!("wav", plan.filler);
+        assert_eq!(vec![Region { range: 44..48, fill: 0 }], plan.regions);
+        assert_eq!(data.len() as u64, plan.len);
+        assert_eq!(data, unchanged);
+    }
+
+    #[test]
+    pub fn test_plan_all() {
+        let file_paths = ["test_plan_all.gif", "test_plan_all.wav", "test_plan_all_missing.gif"];
+        write_data_into_file(file_paths[0], &gif_vec());
+        write_data_into_file(file_paths[1], &wav_vec());
+
+        let plans = plan::plan_all(file_paths, &FillOptions::default());
+
+        fs::remove_file(file_paths[0]).unwrap();
+        fs::remove_file(file_paths[1]).unwrap();
+
+        let gif_plan = plans[0].1.as_ref().unwrap();
+        assert_eq!("any", gif_plan.filler);
+        assert_eq!(gif_vec().len() as u64, gif_plan.overwritten());
+
+        let wav_plan = plans[1].1.as_ref().unwrap();
+        assert_eq!("wav", wav_plan.filler);
+        // 8-bit samples are unsigned, and the pad byte after the odd length data chunk is left alone
+        assert_eq!(vec![Region { range: 44..44 + 0x0EE1, fill: 0x80 }], wav_plan.regions);
+
+        assert!(plans[2].1.is_err());
+    }
+
+    #[test]
+    pub fn test_fill_wav_verify() {
+        let file_path = "test_fill_wav_verify.wav";
+        write_data_into_file(file_path, &wav_vec());
+
+        let options = FillOptions {
+            verify: true,
+            ..FillOptions::default()
+        };
+
+        let res = fill_wav_with(file_path, &options);
+        fs::remove_file(file_path).unwrap();
+
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    pub fn test_verify_wav_structure_changed() {
+        let file_path = "test_verify_wav_structure_changed.wav";
+        let mut data = riff_vec(&[(b"fmt ", &pcm_16_mono_fmt()), (b"data", &[0x11, 0x22, 0x33, 0x44])]);
+        write_data_into_file(file_path, &data);
+
+        let plan = plan_wav(file_path).unwrap();
+
+        // zeroes the samples but halves the sample rate
+        data[24..28].copy_from_slice(&4000u32.to_le_bytes());
+        data[28..32].copy_from_slice(&8000u32.to_le_bytes());
+        data[44..48].copy_from_slice(&[0; 4]);
+        write_data_into_file(file_path, &data);
+
+        let verification = verify_wav(file_path, &plan).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(1, verification.mismatches.len());
+
+        match verification.mismatches[0] {
+            verify::Mismatch::Structure { .. } => (),
+            ref other => panic!("expected a structure mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn test_fill_any() {
+        let file_path = "test_fill_any.gif";
+        write_data_into_file(file_path, &gif_vec());
+
+        // check that the data is valid
+        let at_least_one_not_zero = {
+            let mut buf = Vec::new();
+            File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+            buf.into_iter().any(|d| d != 0)
+        };
+
+        fill_any(file_path).unwrap();
+
+        // check that the file does have all samples filled with zeroes
+        let is_all_zeroes = {
+            let mut buf = Vec::new();
+            File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+            buf.into_iter().all(|d| d == 0)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert!(at_least_one_not_zero);
+        assert!(is_all_zeroes);
+    }
+
+    #[test]
+    pub fn test_c_zero_fill_matching() {
+        let file_path = "test_c_zero_fill_matching.WAV";
+        write_data_into_file(file_path, &wav_vec());
+
+        // check that the samples are valid
+        let at_least_one_not_zero = {
+            let mut reader = WavReader::open(file_path).unwrap();
+            reader.samples::<i16>().any(|s| s.unwrap() != 0)
+        };
+
+        let file_path_with_nul = &format!("{}\0", file_path);
+        let c_file_path = CStr::from_bytes_with_nul(file_path_with_nul.as_bytes()).unwrap();
+        let status = unsafe { zero_fill_matching(c_file_path.as_ptr()) };
+
+        assert!(status == ZERO_FILL_OK);
+
+        // check that the file does have all samples filled with zeroes
+        let is_all_zeroes = {
+            let mut reader = WavReader::open(file_path).unwrap();
+            reader.samples::<i16>().all(|s| s.unwrap() == 0)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert!(at_least_one_not_zero);
+        assert!(is_all_zeroes);
+    }
+
+    #[test]
+    pub fn test_fill_any_in_place() {
+        let file_path = "test_fill_any_in_place.gif";
+        write_data_into_file(file_path, &gif_vec());
+
+        #[cfg(unix)]
+        let ino = {
+            use std::os::unix::fs::MetadataExt;
+            fs::metadata(file_path).unwrap().ino()
+        };
+
+        let options = FillOptions {
+            mode: Mode::InPlace,
+            ..FillOptions::default()
+        };
+
+        fill_any_with(file_path, &options).unwrap();
+
+        let mut buf = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+
+        #[cfg(unix)]
+        let filled_ino = {
+            use std::os::unix::fs::MetadataExt;
+            fs::metadata(file_path).unwrap().ino()
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(gif_vec().len(), buf.len());
+        assert!(buf.into_iter().all(|d| d == 0));
+
+        // the very same file must have been written to
+        #[cfg(unix)]
+        assert_eq!(ino, filled_ino);
+    }
+
+    #[test]
+    pub fn test_fill_any_small_buffer() {
+        let file_path = "test_fill_any_small_buffer.gif";
+        write_data_into_file(file_path, &gif_vec());
+
+        let options = FillOptions {
+            buffer_size: 7,
+            ..FillOptions::default()
+        };
+
+        fill_any_with(file_path, &options).unwrap();
+
+        let mut buf = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(gif_vec().len(), buf.len());
+        assert!(buf.into_iter().all(|d| d == 0));
+    }
+
+    #[test]
+    pub fn test_fill_any_bounded_memory() {
+        let file_path = "test_fill_any_bounded_memory.img";
+        let len = 32u64 << 20;
+
+        {
+            let mut file = File::create(file_path).unwrap();
+            file.write_all(b"not zero").unwrap();
+            file.set_len(len).unwrap();
+            file.seek(SeekFrom::End(-8)).unwrap();
+            file.write_all(b"not zero").unwrap();
+        }
+
+        let options = FillOptions {
+            buffer_size: 4096,
+            ..FillOptions::default()
+        };
+
+        fill_any_with(file_path, &options).unwrap();
+
+        let (filled_len, head, tail) = {
+            let mut file = File::open(file_path).unwrap();
+            let (mut head, mut tail) = ([0xFFu8; 8], [0xFFu8; 8]);
+            file.read_exact(&mut head).unwrap();
+            file.seek(SeekFrom::End(-8)).unwrap();
+            file.read_exact(&mut tail).unwrap();
+            (file.metadata().unwrap().len(), head, tail)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(len, filled_len);
+        assert_eq!([0u8; 8], head);
+        assert_eq!([0u8; 8], tail);
+        assert!(LARGEST_ALLOC.load(Ordering::SeqCst) < (len / 4) as usize);
+    }
+
+    #[test]
+    #[ignore = "writes 4 GiB of zeroes into the working directory"]
+    pub fn test_fill_any_sparse_multi_gb() {
+        let file_path = "test_fill_any_sparse_multi_gb.img";
+        let len = (4u64 << 30) + 1;
+
+        {
+            let mut file = File::create(file_path).unwrap();
+            file.write_all(b"not zero").unwrap();
+            file.set_len(len).unwrap();
+        }
+
+        fill_any(file_path).unwrap();
+
+        let (filled_len, head) = {
+            let mut file = File::open(file_path).unwrap();
+            let mut head = [0xFFu8; 8];
+            file.read_exact(&mut head).unwrap();
+            (file.metadata().unwrap().len(), head)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(len, filled_len);
+        assert_eq!([0u8; 8], head);
+        assert!(LARGEST_ALLOC.load(Ordering::SeqCst) < (1 << 30));
+    }
+
+    #[test]
+    pub fn test_c_zero_fill_batch() {
+        let file_paths = ["test_c_zero_fill_batch_1.gif\0", "test_c_zero_fill_batch_2.wav\0", "test_c_zero_fill_batch_3.gif\0"];
+        write_data_into_file(file_paths[0].trim_end_matches('\0'), &gif_vec());
+        write_data_into_file(file_paths[1].trim_end_matches('\0'), b"RIFF");
+        write_data_into_file(file_paths[2].trim_end_matches('\0'), &gif_vec());
+
+        let c_file_paths: Vec<_> = file_paths.iter().map(|p| p.as_ptr() as *const c_char).collect();
+        let mut statuses = [-1; 3];
+
+        let status = unsafe { zero_fill_batch(c_file_paths.as_ptr(), c_file_paths.len(), 2, statuses.as_mut_ptr()) };
+
+        for file_path in file_paths.iter() {
+            fs::remove_file(file_path.trim_end_matches('\0')).unwrap();
+        }
+
+        // a truncated header is not recognized, so the extension picks the WAV filler, which rejects it
+        assert_eq!(ZERO_FILL_FORMAT, status);
+        assert_eq!([ZERO_FILL_OK, ZERO_FILL_FORMAT, ZERO_FILL_OK], statuses);
+    }
+
+    #[test]
+    pub fn test_c_zero_fill_wav_and_any() {
+        let wav_path = "test_c_zero_fill_wav_and_any.bin";
+        let any_path = "test_c_zero_fill_wav_and_any.wav";
+        write_data_into_file(wav_path, &gif_vec());
+        write_data_into_file(any_path, &wav_vec());
+
+        let c_wav_path = CString::new(wav_path).unwrap();
+        let c_any_path = CString::new(any_path).unwrap();
+        let wav_status = unsafe { zero_fill_wav(c_wav_path.as_ptr()) };
+        let any_status = unsafe { zero_fill_any(c_any_path.as_ptr()) };
+
+        let mut buf = Vec::new();
+        File::open(any_path).unwrap().read_to_end(&mut buf).unwrap();
+        fs::remove_file(wav_path).unwrap();
+        fs::remove_file(any_path).unwrap();
+
+        // the extension is ignored when the filler is forced
+        assert_eq!(ZERO_FILL_FORMAT, wav_status);
+        assert_eq!(ZERO_FILL_OK, any_status);
+        assert_eq!(wav_vec().len(), buf.len());
+        assert!(buf.into_iter().all(|d| d == 0));
+    }
+
+    #[test]
+    pub fn test_c_zero_fill_with_options() {
+        let file_path = "test_c_zero_fill_with_options.wav";
+        write_data_into_file(file_path, &wav_vec());
+
+        let options = CFillOptions {
+            mode: Mode::InPlace,
+            buffer_size: 0,
+            detection: Detection::Extension,
+            verify: true,
+            ..zero_fill_default_options()
+        };
+
+        let c_file_path = CString::new(file_path).unwrap();
+        let status = unsafe { zero_fill_with_options(c_file_path.as_ptr(), &options) };
+        let default_status = unsafe { zero_fill_with_options(c_file_path.as_ptr(), ptr::null()) };
+
+        let is_all_silence = {
+            let mut reader = WavReader::open(file_path).unwrap();
+            reader.samples::<i8>().all(|s| s.unwrap() == 0)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(ZERO_FILL_OK, status);
+        assert_eq!(ZERO_FILL_OK, default_status);
+        assert!(is_all_silence);
+        assert_eq!(DEFAULT_BUFFER_SIZE, FillOptions::from(options).buffer_size);
+        assert_eq!(CFillOptions::from(&FillOptions::default()), zero_fill_default_options());
+    }
+
+    #[test]
+    pub fn test_c_zero_fill_forced_with_options() {
+        let wav_path = "test_c_zero_fill_forced_with_options.bin";
+        let any_path = "test_c_zero_fill_forced_with_options.wav";
+        write_data_into_file(wav_path, &wav_vec());
+        write_data_into_file(any_path, &wav_vec());
+
+        let options = CFillOptions {
+            mode: Mode::InPlace,
+            buffer_size: 16,
+            verify: true,
+            ..zero_fill_default_options()
+        };
+
+        let (c_wav_path, c_any_path) = (CString::new(wav_path).unwrap(), CString::new(any_path).unwrap());
+        let wav_status = unsafe { zero_fill_wav_with_options(c_wav_path.as_ptr(), &options) };
+        let any_status = unsafe { zero_fill_any_with_options(c_any_path.as_ptr(), &options) };
+        let null_status = unsafe { zero_fill_wav_with_options(ptr::null(), ptr::null()) };
+
+        let (wav, any) = (fs::read(wav_path).unwrap(), fs::read(any_path).unwrap());
+        fs::remove_file(wav_path).unwrap();
+        fs::remove_file(any_path).unwrap();
+
+        // the WAV filler is used despite the extension, and the whole file is zeroed despite the content
+        assert_eq!(ZERO_FILL_OK, wav_status);
+        assert_eq!(ZERO_FILL_OK, any_status);
+        assert_eq!(ZERO_FILL_NULL_POINTER, null_status);
+        assert_eq!(fill_wav_buffer(&wav_vec()).unwrap(), wav);
+        assert!(any.into_iter().all(|d| d == 0));
+    }
+
+    #[test]
+    pub fn test_c_zero_fill_null() {
+        let file_path = "test_c_zero_fill_null.bin";
+        write_data_into_file(file_path, b"not zero");
+
+        let c_file_path = CString::new(file_path).unwrap();
+        let c_file_paths = [ptr::null(), c_file_path.as_ptr()];
+        let mut statuses = [-1; 2];
+
+        let matching_status = unsafe { zero_fill_matching(ptr::null()) };
+        let atomic_status = unsafe { zero_fill_matching_atomic(ptr::null()) };
+        let null_array_status = unsafe { zero_fill_batch(ptr::null(), 1, 1, ptr::null_mut()) };
+        let batch_status = unsafe { zero_fill_batch(c_file_paths.as_ptr(), c_file_paths.len(), 1, statuses.as_mut_ptr()) };
+
+        let mut buf = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(ZERO_FILL_NULL_POINTER, matching_status);
+        assert_eq!(ZERO_FILL_NULL_POINTER, atomic_status);
+        assert_eq!(ZERO_FILL_NULL_POINTER, null_array_status);
+        assert_eq!(ZERO_FILL_NULL_POINTER, batch_status);
+        assert_eq!([ZERO_FILL_NULL_POINTER, ZERO_FILL_OK], statuses);
+        assert_eq!(vec![0; 8], buf);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn test_c_zero_fill_non_utf8_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let file_name: &[u8] = b"test_c_zero_fill_non_utf8_\xFF\xFE.bin";
+        let file_path = Path::new(OsStr::from_bytes(file_name));
+        write_data_into_file(file_path, b"not zero");
+
+        let c_file_path = CString::new(file_name).unwrap();
+        let status = unsafe { zero_fill_matching(c_file_path.as_ptr()) };
+
+        let mut buf = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(ZERO_FILL_OK, status);
+        assert_eq!(vec![0; 8], buf);
+    }
+
+    #[test]
+    pub fn test_fill_any_atomic() {
+        let dir = "test_fill_any_atomic";
+        let file_path = Path::new(dir).join("test_fill_any_atomic.gif");
+        fs::create_dir_all(dir).unwrap();
+        write_data_into_file(&file_path, &gif_vec());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&file_path, fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        let options = FillOptions {
+            atomic: true,
+            ..FillOptions::default()
+        };
+
+        fill_any_with(&file_path, &options).unwrap();
+
+        let mut buf = Vec::new();
+        File::open(&file_path).unwrap().read_to_end(&mut buf).unwrap();
+        let permissions = fs::metadata(&file_path).unwrap().permissions();
+
+        // no temporary file may be left behind
+        let entries = fs::read_dir(dir).unwrap().count();
+        fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(gif_vec().len(), buf.len());
+        assert!(buf.into_iter().all(|d| d == 0));
+        assert_eq!(1, entries);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(0o640, permissions.mode() & 0o777);
+        }
+
+        #[cfg(not(unix))]
+        let _ = permissions;
+    }
+
+    #[test]
+    pub fn test_c_zero_fill_matching_atomic() {
+        let file_path = "test_c_zero_fill_matching_atomic.wav";
+        write_data_into_file(file_path, &wav_vec());
+
+        let file_path_with_nul = &format!("{}\0", file_path);
+        let c_file_path = CStr::from_bytes_with_nul(file_path_with_nul.as_bytes()).unwrap();
+        let status = unsafe { zero_fill_matching_atomic(c_file_path.as_ptr()) };
+
+        assert!(status == ZERO_FILL_OK);
+
+        let is_all_zeroes = {
+            let mut reader = WavReader::open(file_path).unwrap();
+            reader.samples::<i16>().all(|s| s.unwrap() == 0)
+        };
+
+        fs::remove_file(file_path).unwrap();
+
+        assert!(is_all_zeroes);
+    }
+
+    #[test]
+    pub fn test_fill_wav_stream() {
+        let mut cursor = Cursor::new(wav_vec());
+        fill_wav_stream(&mut cursor, &FillOptions::default()).unwrap();
+        let filled = cursor.into_inner();
+
+        let is_all_silence = WavReader::new(Cursor::new(&filled)).unwrap()
+            .samples::<i8>()
+            .all(|s| s.unwrap() == 0);
+
+        assert_eq!(wav_vec().len(), filled.len());
+        assert_eq!(&wav_vec()[..44], &filled[..44]);
+        assert!(is_all_silence);
+        assert_eq!(filled, fill_wav_buffer(&wav_vec()).unwrap());
+        assert_eq!(ZERO_FILL_FORMAT, status_code(&fill_wav_buffer(&gif_vec()).unwrap_err()));
+    }
+
+    #[test]
+    pub fn test_fill_any_stream() {
+        let mut cursor = Cursor::new(b"not zero".to_vec());
+        cursor.set_position(4);
+
+        let options = FillOptions {
+            buffer_size: 3,
+            ..FillOptions::default()
+        };
+
+        fill_any_stream(&mut cursor, &options).unwrap();
+
+        assert_eq!(vec![0; 8], cursor.into_inner());
+        assert_eq!(vec![0; 3], fill_any_buffer(b"abc"));
+    }
+
+    #[test]
+    pub fn test_fill_matching_stream() {
+        let mut wav = Cursor::new(wav_vec());
+        let mut aiff = Cursor::new(aiff_vec(Some(b"sowt"), 16, 2));
+        let mut gif = Cursor::new(gif_vec());
+
+        fill_matching_stream(&mut wav, &FillOptions::default()).unwrap();
+        fill_matching_stream(&mut aiff, &FillOptions::default()).unwrap();
+        fill_matching_stream(&mut gif, &FillOptions::default()).unwrap();
+
+        // the AIFF-C frames start after 2 bytes of SSND offset padding, the rest of the file is kept
+        let original = aiff_vec(Some(b"sowt"), 16, 2);
+        let sound_start = original.windows(4).position(|w| w == b"SSND").unwrap() + 8 + 8 + 2;
+        let mut expected = original.clone();
+        expected[sound_start..sound_start + 12].iter_mut().for_each(|d| *d = 0);
+
+        assert_eq!(fill_wav_buffer(&wav_vec()).unwrap(), wav.into_inner());
+        assert_eq!(expected, aiff.into_inner());
+
+        // zeroing a FLAC, RIFX or Ogg stream whole would leave it undecodable
+        for audio in &[&b"fLaC\x80\x00\x00\x22"[..], b"RIFX\x00\x00\x00\x24WAVEfmt ", b"OggS\x00\x02\x00\x00"] {
+            let mut stream = Cursor::new(audio.to_vec());
+            let e = fill_matching_stream(&mut stream, &FillOptions::default()).unwrap_err();
+            assert_eq!(ZERO_FILL_UNSUPPORTED, status_code(&e));
+            assert_eq!(audio.to_vec(), stream.into_inner());
+        }
+
+        assert!(gif.into_inner().into_iter().all(|d| d == 0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn test_c_zero_fill_fd() {
+        use std::os::unix::io::AsRawFd;
+
+        let file_path = "test_c_zero_fill_fd.bin";
+        write_data_into_file(file_path, &wav_vec());
+
+        let mut file = OpenOptions::new().read(true).write(true).open(file_path).unwrap();
+        let status = unsafe { zero_fill_fd(file.as_raw_fd()) };
+
+        // the descriptor is still open for the caller
+        let mut filled = Vec::new();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.read_to_end(&mut filled).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(ZERO_FILL_OK, status);
+        assert_eq!(fill_wav_buffer(&wav_vec()).unwrap(), filled);
+        assert_eq!(ZERO_FILL_INVALID_ARGUMENT, unsafe { zero_fill_fd(-1) });
+    }
+
+    #[test]
+    pub fn test_c_last_error() {
+        let file_path = "test_c_last_error_missing.bin";
+
+        let c_file_path = CString::new(file_path).unwrap();
+        let status = unsafe { zero_fill_matching(c_file_path.as_ptr()) };
+
+        let mut buf = [0 as c_char; 256];
+        let len = unsafe { zero_fill_last_error_message(buf.as_mut_ptr(), buf.len()) };
+        let message = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap().to_owned();
+
+        let mut short = [0x7F as c_char; 8];
+        let short_len = unsafe { zero_fill_last_error_message(short.as_mut_ptr(), short.len()) };
+        let short_message = unsafe { CStr::from_ptr(short.as_ptr()) }.to_str().unwrap().to_owned();
+
+        assert_eq!(ZERO_FILL_IO, status);
+        assert_eq!(message.len(), len);
+        assert!(message.contains(file_path));
+        assert_eq!(len, short_len);
+        assert_eq!(&message[..7], short_message);
+
+        #[cfg(unix)]
+        assert_eq!(2, zero_fill_last_os_error());
+
+        // a successful call clears the last error
+        write_data_into_file(file_path, b"not zero");
+        let status = unsafe { zero_fill_matching(c_file_path.as_ptr()) };
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(ZERO_FILL_OK, status);
+        assert_eq!(0, unsafe { zero_fill_last_error_message(ptr::null_mut(), 0) });
+        assert_eq!(0, zero_fill_last_os_error());
+    }
+
+    #[test]
+    pub fn test_c_header_up_to_date() {
+        // regenerate the header with `ZERO_FILL_UPDATE_HEADER=1 cargo test`
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let header_path = crate_dir.join("include/zero_fill.h");
+
+        let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+
+        let mut generated = Vec::new();
+
+        cbindgen::Builder::new()
+            .with_src(crate_dir.join("src/lib.rs"))
+            .with_config(config)
+            .generate()
+            .unwrap()
+            .write(&mut generated);
+
+        if env::var_os("ZERO_FILL_UPDATE_HEADER").is_some() {
+            fs::create_dir_all(header_path.parent().unwrap()).unwrap();
+            write_data_into_file(&header_path, &generated);
+        }
+
+        let mut header = Vec::new();
+        File::open(&header_path).unwrap().read_to_end(&mut header).unwrap();
+
+        assert!(header == generated, "{} is out of date", header_path.display());
     }
 }
\ No newline at end of file
@@ -0,0 +1,67 @@
+use super::error::{self, ResultExt};
+use super::{registry, FillOptions};
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub range: Range<u64>,
+    /// Byte written over the whole range, which is the silence value for audio.
+    /// Fillers that re-encode the range instead, like FLAC, use 0.
+    pub fill: u8,
+}
+
+/// Describes what zero filling a file would do, without touching the file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plan {
+    /// Name of the filler that would be used.
+    pub filler: String,
+    /// Byte ranges that would be overwritten. Every other byte is kept as it is.
+    pub regions: Vec<Region>,
+    /// Size of the file after filling.
+    pub len: u64,
+    /// Summary of the file structure that must survive filling, e.g. the WAV spec and sample count.
+    pub structure: Option<String>,
+}
+
+impl Plan {
+    /// A plan that overwrites every byte of a file of size `len` with zeroes.
+    pub fn whole_file(filler: &str, len: u64) -> Plan {
+        let regions = if len > 0 {
+            vec![Region { range: 0..len, fill: 0 }]
+        } else {
+            Vec::new()
+        };
+
+        Plan {
+            filler: filler.to_owned(),
+            regions,
+            len,
+            structure: None,
+        }
+    }
+
+    /// Total number of bytes that would be overwritten.
+    pub fn overwritten(&self) -> u64 {
+        self.regions.iter().map(|region| region.range.end - region.range.start).sum()
+    }
+}
+
+/// Plans `fill_matching` on the file, picking the filler in the same way.
+pub fn plan<P: AsRef<Path>>(file_path: P, options: &FillOptions) -> error::Result<Plan> {
+    let file_path = file_path.as_ref();
+    let filler = registry::find(file_path, options.detection).for_path(file_path)?;
+    filler.plan(file_path, options).for_path(file_path)
+}
+
+pub fn plan_all<I, P>(file_paths: I, options: &FillOptions) -> Vec<(PathBuf, error::Result<Plan>)>
+    where I: IntoIterator<Item = P>, P: AsRef<Path> {
+
+    file_paths.into_iter()
+        .map(|file_path| {
+            let file_path = file_path.as_ref();
+            (file_path.to_owned(), plan(file_path, options))
+        })
+        .collect()
+}
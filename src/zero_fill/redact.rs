@@ -0,0 +1,231 @@
+use super::error::{self, Error, ResultExt};
+use super::plan::{Plan, Region};
+use super::registry::FileFiller;
+use super::verify::{self, Verification};
+use super::wav::{self, Wav};
+use super::{fill_regions, read_wav_repairs, FillOptions, Patch, Recovery};
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+use std::path::Path;
+
+/// A span of a recording, from `start` up to but not including `end`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeRange {
+    /// In seconds from the start of the recording.
+    Seconds(f64, f64),
+    /// In sample frames, which hold one sample of every channel.
+    Frames(u64, u64),
+}
+
+impl TimeRange {
+    /// Returns the frames the range covers at `sample_rate` Hz. Seconds are widened to whole frames,
+    /// so that a frame the range only partly covers is silenced too.
+    fn frames(self, sample_rate: u32) -> error::Result<Range<u64>> {
+        match self {
+            TimeRange::Seconds(start, end) if start >= 0.0 && start <= end && end.is_finite() => {
+                let rate = f64::from(sample_rate);
+                Ok((start * rate).floor() as u64..(end * rate).ceil() as u64)
+            },
+
+            TimeRange::Frames(start, end) if start <= end => Ok(start..end),
+            _ => Err(Error::invalid_argument(format!("invalid time range {}", self))),
+        }
+    }
+}
+
+impl Display for TimeRange {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            TimeRange::Seconds(start, end) => write!(f, "{}s-{}s", start, end),
+            TimeRange::Frames(start, end) => write!(f, "frames {}-{}", start, end),
+        }
+    }
+}
+
+pub fn redact_wav<P: AsRef<Path>>(file_path: P, ranges: &[TimeRange]) -> error::Result<()> {
+    redact_wav_with(file_path, ranges, &FillOptions::default())
+}
+
+/// Replaces the samples of a WAV file within the given ranges with silence, keeping the rest of the audio
+/// and every other chunk as they are. Ranges may overlap and may run past the end of the recording,
+/// but none may start after it.
+pub fn redact_wav_with<P: AsRef<Path>>(file_path: P, ranges: &[TimeRange], options: &FillOptions) -> error::Result<()> {
+    verify::fill_verified(&RedactFiller { ranges }, file_path.as_ref(), options)
+}
+
+/// Silences the given ranges of WAV files. It is not registered, as it only applies when ranges are given.
+pub struct RedactFiller<'a> {
+    pub ranges: &'a [TimeRange],
+}
+
+impl<'a> FileFiller for RedactFiller<'a> {
+    fn name(&self) -> &str {
+        "redact"
+    }
+
+    fn detect(&self, _: &Path) -> bool {
+        false
+    }
+
+    fn fill(&self, file_path: &Path, options: &FillOptions) -> error::Result<()> {
+        // planned before anything is written so that unsupported files and invalid ranges leave the file untouched
+        let (plan, repairs) = plan_repairs(file_path, self.ranges, options)?;
+        fill_regions(file_path, &plan.regions, &repairs, options)
+    }
+
+    fn plan(&self, file_path: &Path, options: &FillOptions) -> error::Result<Plan> {
+        plan_redact_wav(file_path, self.ranges, options)
+    }
+
+    fn verify(&self, file_path: &Path, plan: &Plan) -> error::Result<Verification> {
+        // replanned leniently, as in `verify_wav`, so that recovered files that were not repaired still verify
+        let options = FillOptions {
+            recovery: Recovery::Lenient,
+            ..FillOptions::default()
+        };
+
+        verify::verify_replanned(file_path, plan, |file_path| plan_redact_wav(file_path, self.ranges, &options))
+    }
+}
+
+/// Plans `redact_wav_with`, with a region for every run of frames to be silenced.
+pub fn plan_redact_wav<P: AsRef<Path>>(file_path: P, ranges: &[TimeRange], options: &FillOptions) -> error::Result<Plan> {
+    plan_repairs(file_path.as_ref(), ranges, options).map(|(plan, _)| plan)
+}
+
+fn plan_repairs(file_path: &Path, ranges: &[TimeRange], options: &FillOptions) -> error::Result<(Plan, Vec<Patch>)> {
+    let plan = || -> error::Result<(Plan, Vec<Patch>)> {
+        let (wav, len, repairs) = read_wav_repairs(file_path, options)?;
+
+        // named after `RedactFiller`, as the verification of the WAV filler expects the whole data chunk to be silent
+        let plan = Plan {
+            filler: "redact".to_owned(),
+            regions: regions(&wav, ranges)?,
+            len,
+            structure: Some(wav::describe(&wav)),
+        };
+
+        Ok((plan, repairs))
+    };
+
+    plan().for_path(file_path)
+}
+
+/// Converts the ranges into sorted and merged byte ranges of the `data` chunk.
+fn regions(wav: &Wav, ranges: &[TimeRange]) -> error::Result<Vec<Region>> {
+    let block_align = u64::from(wav.fmt.block_align);
+    let frames = (wav.data.end - wav.data.start) / block_align;
+
+    let mut spans = Vec::with_capacity(ranges.len());
+
+    for &range in ranges {
+        let span = range.frames(wav.fmt.sample_rate)?;
+
+        if span.start > frames {
+            return Err(Error::invalid_argument(format!("time range {} starts after the end of the recording", range)));
+        }
+
+        // a trailing partial frame is silenced along with the last whole one
+        let start = wav.data.start + span.start * block_align;
+        let end = if span.end >= frames { wav.data.end } else { wav.data.start + span.end * block_align };
+
+        if start < end {
+            spans.push(start..end);
+        }
+    }
+
+    spans.sort_by_key(|span| span.start);
+
+    let mut regions: Vec<Region> = Vec::with_capacity(spans.len());
+
+    for span in spans {
+        match regions.last_mut() {
+            Some(ref mut region) if span.start <= region.range.end => region.range.end = region.range.end.max(span.end),
+            _ => regions.push(Region { range: span, fill: wav.silence }),
+        }
+    }
+
+    Ok(regions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::{plan_wav, status_code, Mode, ZERO_FILL_INVALID_ARGUMENT};
+    use hound::{self, WavReader, WavWriter};
+    use std::fs;
+
+    /// A 2 channel 16-bit WAV file at 1000 Hz with 10 frames, every sample 0x1111.
+    fn write_wav(file_path: &str) {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 1000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let mut writer = WavWriter::create(file_path, spec).unwrap();
+
+        for _ in 0..20 {
+            writer.write_sample(0x1111i16).unwrap();
+        }
+
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    pub fn test_redact_wav() {
+        let file_path = "test_redact_wav.wav";
+
+        // 2.5 ms is widened to frame 2, overlapping ranges are merged and the last range is cut at the end
+        let ranges = [TimeRange::Seconds(0.0025, 0.004), TimeRange::Frames(3, 6), TimeRange::Frames(8, 100)];
+
+        for &mode in &[Mode::Rewrite, Mode::InPlace] {
+            write_wav(file_path);
+
+            let options = FillOptions {
+                mode,
+                verify: true,
+                ..FillOptions::default()
+            };
+
+            redact_wav_with(file_path, &ranges, &options).unwrap();
+
+            let samples: Vec<i16> = WavReader::open(file_path).unwrap().samples().map(|s| s.unwrap()).collect();
+            fs::remove_file(file_path).unwrap();
+
+            let frames: Vec<_> = samples.chunks(2).map(|frame| frame[0] == 0 && frame[1] == 0).collect();
+            assert_eq!(vec![false, false, true, true, true, true, false, false, true, true], frames);
+            assert!(samples.iter().all(|&s| s == 0 || s == 0x1111));
+        }
+    }
+
+    #[test]
+    pub fn test_plan_redact_wav() {
+        let file_path = "test_plan_redact_wav.wav";
+        write_wav(file_path);
+
+        let plan = plan_redact_wav(file_path, &[TimeRange::Frames(4, 5), TimeRange::Seconds(0.0, 0.001)],
+            &FillOptions::default()).unwrap();
+        let whole = plan_wav(file_path).unwrap();
+
+        let invalid: Vec<_> = [TimeRange::Seconds(0.002, 0.001), TimeRange::Seconds(-1.0, 0.0), TimeRange::Frames(11, 12)]
+            .iter()
+            .map(|&range| redact_wav(file_path, &[range]).unwrap_err())
+            .collect();
+
+        let unchanged = fs::read(file_path).unwrap();
+        write_wav(file_path);
+        let original = fs::read(file_path).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!("redact", plan.filler);
+        assert_eq!(vec![Region { range: 68..72, fill: 0 }, Region { range: 84..88, fill: 0 }], plan.regions);
+        assert_eq!(108, plan.len);
+        assert_eq!(whole.structure, plan.structure);
+
+        assert!(invalid.iter().all(|e| status_code(e) == ZERO_FILL_INVALID_ARGUMENT));
+        assert_eq!(original, unchanged);
+    }
+}
@@ -0,0 +1,373 @@
+use super::aiff::{fill_aiff_unverified, plan_aiff, verify_aiff};
+use super::detect::{self, Detection, Format};
+use super::error::{Error, Operation, ResultExt};
+use super::flac::{fill_flac_unverified, plan_flac, verify_flac};
+use super::plan::Plan;
+use super::verify::{self, Verification};
+use super::{error, fill_any_unverified, fill_wav_unverified, plan_any, plan_wav_with, verify_any, verify_wav, FillOptions};
+
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+pub trait FileFiller: Send + Sync {
+    /// Short name identifying the filler, e.g. `"wav"`.
+    fn name(&self) -> &str;
+
+    /// Returns whether this filler knows how to zero fill the given file judging from its path alone.
+    fn detect(&self, file_path: &Path) -> bool;
+
+    /// Returns whether this filler knows how to zero fill a file starting with `head`,
+    /// which holds up to `detect::SNIFF_LEN` bytes.
+    fn sniff(&self, _head: &[u8]) -> bool {
+        false
+    }
+
+    fn fill(&self, file_path: &Path, options: &FillOptions) -> error::Result<()>;
+
+    /// Describes what `fill` would do without touching the file. Unless overridden,
+    /// the whole file is assumed to be overwritten with zeroes.
+    fn plan(&self, file_path: &Path, _options: &FillOptions) -> error::Result<Plan> {
+        Ok(Plan::whole_file(self.name(), fs::metadata(file_path)?.len()))
+    }
+
+    /// Checks the filled file against a plan made before filling. Unless overridden,
+    /// only the file size and the content of the planned regions are checked.
+    fn verify(&self, file_path: &Path, plan: &Plan) -> error::Result<Verification> {
+        verify::verify_regions(file_path, plan)
+    }
+}
+
+pub struct WavFiller;
+
+impl FileFiller for WavFiller {
+    fn name(&self) -> &str {
+        "wav"
+    }
+
+    fn detect(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["wav"])
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        matches!(detect::sniff(head), Some(Format::Wav) | Some(Format::Rf64) | Some(Format::Bw64))
+    }
+
+    fn fill(&self, file_path: &Path, options: &FillOptions) -> error::Result<()> {
+        fill_wav_unverified(file_path, options)
+    }
+
+    fn plan(&self, file_path: &Path, options: &FillOptions) -> error::Result<Plan> {
+        plan_wav_with(file_path, options)
+    }
+
+    fn verify(&self, file_path: &Path, plan: &Plan) -> error::Result<Verification> {
+        verify_wav(file_path, plan)
+    }
+}
+
+pub struct AiffFiller;
+
+impl FileFiller for AiffFiller {
+    fn name(&self) -> &str {
+        "aiff"
+    }
+
+    fn detect(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["aif", "aiff", "aifc"])
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        matches!(detect::sniff(head), Some(Format::Aiff) | Some(Format::Aifc))
+    }
+
+    fn fill(&self, file_path: &Path, options: &FillOptions) -> error::Result<()> {
+        fill_aiff_unverified(file_path, options)
+    }
+
+    fn plan(&self, file_path: &Path, _: &FillOptions) -> error::Result<Plan> {
+        plan_aiff(file_path)
+    }
+
+    fn verify(&self, file_path: &Path, plan: &Plan) -> error::Result<Verification> {
+        verify_aiff(file_path, plan)
+    }
+}
+
+pub struct FlacFiller;
+
+impl FileFiller for FlacFiller {
+    fn name(&self) -> &str {
+        "flac"
+    }
+
+    fn detect(&self, file_path: &Path) -> bool {
+        has_extension(file_path, &["flac"])
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        detect::sniff(head) == Some(Format::Flac)
+    }
+
+    fn fill(&self, file_path: &Path, options: &FillOptions) -> error::Result<()> {
+        fill_flac_unverified(file_path, options)
+    }
+
+    fn plan(&self, file_path: &Path, _: &FillOptions) -> error::Result<Plan> {
+        plan_flac(file_path)
+    }
+
+    fn verify(&self, file_path: &Path, plan: &Plan) -> error::Result<Verification> {
+        verify_flac(file_path, plan)
+    }
+}
+
+pub struct AnyFiller;
+
+impl FileFiller for AnyFiller {
+    fn name(&self) -> &str {
+        "any"
+    }
+
+    fn detect(&self, _: &Path) -> bool {
+        true
+    }
+
+    fn fill(&self, file_path: &Path, options: &FillOptions) -> error::Result<()> {
+        fill_any_unverified(file_path, options)
+    }
+
+    fn plan(&self, file_path: &Path, _: &FillOptions) -> error::Result<Plan> {
+        plan_any(file_path)
+    }
+
+    fn verify(&self, file_path: &Path, plan: &Plan) -> error::Result<Verification> {
+        verify_any(file_path, plan)
+    }
+}
+
+pub struct Registry {
+    fillers: Vec<Arc<dyn FileFiller>>,
+    fallback: Arc<dyn FileFiller>,
+}
+
+impl Registry {
+    /// Creates a registry with the built-in fillers, falling back to `AnyFiller`.
+    pub fn new() -> Registry {
+        let mut registry = Registry {
+            fillers: Vec::new(),
+            fallback: Arc::new(AnyFiller),
+        };
+
+        registry.register(WavFiller);
+        registry.register(AiffFiller);
+        registry.register(FlacFiller);
+        registry
+    }
+
+    /// Registers a filler, which takes precedence over all previously registered fillers.
+    pub fn register<F: FileFiller + 'static>(&mut self, filler: F) {
+        self.fillers.insert(0, Arc::new(filler));
+    }
+
+    pub fn find(&self, file_path: &Path, detection: Detection) -> error::Result<Arc<dyn FileFiller>> {
+        if detection != Detection::Extension {
+            let head = detect::read_head(file_path).during(Operation::ReadHeader)?;
+
+            if let Some(filler) = self.fillers.iter().find(|filler| filler.sniff(&head)) {
+                return Ok(filler.clone());
+            }
+
+            let format = detect::sniff(&head);
+
+            // zeroing a whole audio file would destroy its header, so audio that no filler handles is left alone
+            if let Some(format) = format.filter(|format| format.is_audio()) {
+                return Err(Error::unsupported(format!("{:?} audio", format))).for_path(file_path);
+            }
+
+            // the extension is only a hint for content that is not recognized, a GIF named .wav is still a GIF
+            if detection == Detection::Content || format.is_some() {
+                return Ok(self.fallback.clone());
+            }
+        }
+
+        Ok(self.fillers.iter().find(|filler| filler.detect(file_path)).unwrap_or(&self.fallback).clone())
+    }
+
+    /// Finds the filler with the given name, including the fallback.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn FileFiller>> {
+        self.fillers.iter()
+            .chain(Some(&self.fallback))
+            .find(|filler| filler.name() == name)
+            .cloned()
+    }
+
+    pub fn fill(&self, file_path: &Path, options: &FillOptions) -> error::Result<()> {
+        verify::fill_verified(&*self.find(file_path, options.detection)?, file_path, options)
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        Registry::new()
+    }
+}
+
+/// The registry consulted by `fill_matching`.
+pub fn global() -> &'static RwLock<Registry> {
+    static GLOBAL: OnceLock<RwLock<Registry>> = OnceLock::new();
+    GLOBAL.get_or_init(|| RwLock::new(Registry::new()))
+}
+
+/// Registers a filler into the global registry.
+pub fn register<F: FileFiller + 'static>(filler: F) {
+    global().write().unwrap_or_else(|e| e.into_inner()).register(filler)
+}
+
+/// Finds the filler in the global registry for the given file.
+pub fn find(file_path: &Path, detection: Detection) -> error::Result<Arc<dyn FileFiller>> {
+    // the lock is released before filling so that registering is never blocked by I/O
+    global().read().unwrap_or_else(|e| e.into_inner()).find(file_path, detection)
+}
+
+fn has_extension(file_path: &Path, exts: &[&str]) -> bool {
+    match file_path.extension() {
+        Some(ext) => {
+            let ext = ext.to_string_lossy().to_lowercase();
+            exts.iter().any(|e| *e == ext)
+        },
+
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::error::ErrorKind;
+    use super::super::fill_matching;
+    use std::fs::{self, File};
+    use std::io::{Read, Write};
+
+    struct MarkerFiller;
+
+    impl FileFiller for MarkerFiller {
+        fn name(&self) -> &str {
+            "marker"
+        }
+
+        fn detect(&self, file_path: &Path) -> bool {
+            has_extension(file_path, &["marker"])
+        }
+
+        fn fill(&self, file_path: &Path, _: &FillOptions) -> error::Result<()> {
+            Ok(File::create(file_path)?.write_all(b"marked")?)
+        }
+    }
+
+    #[test]
+    pub fn test_registry_defaults() {
+        let registry = Registry::new();
+        let find = |file_path| registry.find(Path::new(file_path), Detection::Extension).unwrap();
+
+        assert_eq!("wav", find("a.WAV").name());
+        assert_eq!("aiff", find("a.aif").name());
+        assert_eq!("aiff", find("a.AIFC").name());
+        assert_eq!("flac", find("a.flac").name());
+        assert_eq!("any", find("a.gif").name());
+        assert_eq!("any", find("a").name());
+    }
+
+    #[test]
+    pub fn test_registry_detection() {
+        let gif_path = "test_registry_detection.wav";
+        let text_path = "test_registry_detection_text.wav";
+        File::create(gif_path).unwrap().write_all(b"GIF89a\x64\x00\x64\x00").unwrap();
+        File::create(text_path).unwrap().write_all(b"plain text").unwrap();
+
+        let registry = Registry::new();
+        let find = |file_path, detection| registry.find(Path::new(file_path), detection).unwrap().name().to_owned();
+        let (auto, content, extension) = (find(gif_path, Detection::Auto), find(gif_path, Detection::Content),
+            find(gif_path, Detection::Extension));
+        let (text_auto, text_content) = (find(text_path, Detection::Auto), find(text_path, Detection::Content));
+
+        fs::remove_file(gif_path).unwrap();
+        fs::remove_file(text_path).unwrap();
+
+        // recognized content that no filler sniffs goes to the fallback, whatever the extension
+        assert_eq!("any", auto);
+        assert_eq!("any", content);
+        assert_eq!("wav", extension);
+
+        // only unrecognized content falls back to the extension
+        assert_eq!("wav", text_auto);
+        assert_eq!("any", text_content);
+    }
+
+    #[test]
+    pub fn test_registry_unsupported_audio() {
+        let rifx_path = "test_registry_unsupported_audio.wav";
+        let ogg_path = "test_registry_unsupported_audio.ogg";
+        File::create(rifx_path).unwrap().write_all(b"RIFX\x00\x00\x00\x24WAVEfmt ").unwrap();
+        File::create(ogg_path).unwrap().write_all(b"OggS\x00\x02\x00\x00\x00\x00\x00\x00").unwrap();
+
+        let registry = Registry::new();
+        let unsupported = |file_path, detection| match registry.find(Path::new(file_path), detection) {
+            Err(ref e) => matches!(*e.kind(), ErrorKind::Unsupported(_)),
+            Ok(_) => false,
+        };
+
+        let (rifx_auto, rifx_content, ogg_auto) = (unsupported(rifx_path, Detection::Auto),
+            unsupported(rifx_path, Detection::Content), unsupported(ogg_path, Detection::Auto));
+        let filled = fill_matching(rifx_path);
+        let rifx = fs::read(rifx_path).unwrap();
+
+        fs::remove_file(rifx_path).unwrap();
+        fs::remove_file(ogg_path).unwrap();
+
+        assert!(rifx_auto);
+        assert!(rifx_content);
+        assert!(ogg_auto);
+        assert!(filled.is_err());
+        assert_eq!(b"RIFX\x00\x00\x00\x24WAVEfmt ".to_vec(), rifx);
+    }
+
+    #[test]
+    pub fn test_register_takes_precedence() {
+        struct AllFiller;
+
+        impl FileFiller for AllFiller {
+            fn name(&self) -> &str {
+                "all"
+            }
+
+            fn detect(&self, _: &Path) -> bool {
+                true
+            }
+
+            fn fill(&self, _: &Path, _: &FillOptions) -> error::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut registry = Registry::new();
+        registry.register(AllFiller);
+
+        assert_eq!("all", registry.find(Path::new("a.wav"), Detection::Extension).unwrap().name());
+    }
+
+    #[test]
+    pub fn test_fill_matching_uses_global_registry() {
+        let file_path = "test_fill_matching_uses_global_registry.marker";
+        File::create(file_path).unwrap().write_all(b"original").unwrap();
+
+        register(MarkerFiller);
+        fill_matching(file_path).unwrap();
+
+        let mut buf = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(b"marked".to_vec(), buf);
+    }
+}
@@ -0,0 +1,91 @@
+use super::error::{self, Error, ErrorKind, Operation, ResultExt};
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+pub fn replace_with<F>(file_path: &Path, atomic: bool, write: F) -> error::Result<()>
+    where F: FnOnce(&mut BufWriter<File>) -> error::Result<()> {
+
+    if !atomic {
+        let mut writer = BufWriter::new(File::create(file_path).during(Operation::Open)?);
+        write(&mut writer).during(Operation::Write)?;
+        return writer.flush().during(Operation::Finalize);
+    }
+
+    // replaces the target of a symbolic link rather than the link itself
+    let file_path = &fs::canonicalize(file_path).during(Operation::Open)?;
+    let metadata = fs::metadata(file_path).during(Operation::Open)?;
+    let tmp_path = tmp_path_for(file_path)?;
+
+    let file = OpenOptions::new().write(true).create_new(true).open(&tmp_path)
+        .during(Operation::Open)
+        .for_path(&tmp_path)?;
+
+    let res = write_synced(file, write)
+        .and_then(|_| copy_ownership(&tmp_path, &metadata).during(Operation::Finalize))
+        .and_then(|_| fs::set_permissions(&tmp_path, metadata.permissions()).during(Operation::Finalize))
+        .and_then(|_| fs::rename(&tmp_path, file_path).during(Operation::Finalize));
+
+    if res.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+        return res;
+    }
+
+    sync_parent(file_path).during(Operation::Finalize)
+}
+
+fn write_synced<F>(file: File, write: F) -> error::Result<()>
+    where F: FnOnce(&mut BufWriter<File>) -> error::Result<()> {
+
+    let mut writer = BufWriter::new(file);
+    write(&mut writer).during(Operation::Write)?;
+
+    let file = writer.into_inner().map_err(|e| e.into_error()).during(Operation::Finalize)?;
+    file.sync_all().during(Operation::Finalize)
+}
+
+fn tmp_path_for(file_path: &Path) -> error::Result<PathBuf> {
+    let file_name = file_path.file_name().ok_or_else(|| Error::new(ErrorKind::Filename))?;
+
+    // the temporary file must be on the same file system for the rename to be atomic
+    let mut name = OsString::from(".");
+    name.push(file_name);
+    name.push(format!(".{}.zero_fill.tmp", process::id()));
+    Ok(file_path.with_file_name(name))
+}
+
+#[cfg(unix)]
+fn copy_ownership(tmp_path: &Path, metadata: &fs::Metadata) -> error::Result<()> {
+    use std::os::unix::fs::{chown, MetadataExt};
+
+    let tmp_metadata = fs::metadata(tmp_path)?;
+
+    if tmp_metadata.uid() != metadata.uid() || tmp_metadata.gid() != metadata.gid() {
+        chown(tmp_path, Some(metadata.uid()), Some(metadata.gid()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_ownership(_: &Path, _: &fs::Metadata) -> error::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn sync_parent(file_path: &Path) -> error::Result<()> {
+    let parent = match file_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    Ok(File::open(parent)?.sync_all()?)
+}
+
+#[cfg(not(unix))]
+fn sync_parent(_: &Path) -> error::Result<()> {
+    Ok(())
+}
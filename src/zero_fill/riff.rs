@@ -0,0 +1,307 @@
+use super::error::{self, Error};
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Range;
+
+const RIFF_HEADER_LEN: u64 = 12;
+const CHUNK_HEADER_LEN: u64 = 8;
+/// The RIFF, data and sample count sizes and the table length of a `ds64` chunk.
+const DS64_LEN: usize = 28;
+const DS64_ENTRY_LEN: usize = 12;
+/// The 32-bit size field value that defers to the `ds64` chunk.
+const UNKNOWN_SIZE: u32 = 0xFFFF_FFFF;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Chunk {
+    pub id: [u8; 4],
+    pub offset: u64,
+    pub len: u64,
+}
+
+impl Chunk {
+    pub fn payload(&self) -> Range<u64> {
+        let start = self.offset + CHUNK_HEADER_LEN;
+        start..start.saturating_add(self.len)
+    }
+
+    /// Whether the payload ends within a file of `file_len` bytes, without overflowing on 64-bit sizes.
+    fn fits(&self, file_len: u64) -> bool {
+        self.len <= file_len.saturating_sub(self.offset + CHUNK_HEADER_LEN)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// RIFF, as used by WAV.
+    Little,
+    /// IFF, as used by AIFF.
+    Big,
+}
+
+/// Where the sizes of a form and its chunks are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sizes {
+    /// In the 32-bit size fields of the headers.
+    Header,
+    /// In a `ds64` chunk right after the form header, for every 32-bit size field set to 0xFFFFFFFF,
+    /// as in RF64 and BW64.
+    Ds64,
+}
+
+/// The 64-bit sizes of a `ds64` chunk.
+struct Ds64 {
+    riff: u64,
+    data: u64,
+    /// Sizes of chunks other than `data`, by chunk id.
+    table: Vec<([u8; 4], u64)>,
+}
+
+/// Reads the chunks of a RIFF, RF64 or BW64 WAVE file.
+pub fn read_chunks<R: Read + Seek>(reader: &mut R) -> error::Result<Vec<Chunk>> {
+    read_form_with(reader, ByteOrder::Little, check_wave, false)
+}
+
+/// Same as `read_chunks`, but for a file whose recording was cut short, where the sizes in the headers
+/// were never updated. The RIFF size is ignored, and a `data` chunk with a size of 0, 0xFFFFFFFF or past
+/// the end of the file is taken to run to the end of the file. Chunks after the `data` chunk that do not
+/// fit in the file are dropped.
+pub fn recover_chunks<R: Read + Seek>(reader: &mut R) -> error::Result<Vec<Chunk>> {
+    read_form_with(reader, ByteOrder::Little, check_wave, true)
+}
+
+fn check_wave(header: &[u8; RIFF_HEADER_LEN as usize]) -> error::Result<Sizes> {
+    match (&header[0..4], &header[8..12]) {
+        (b"RIFF", b"WAVE") => Ok(Sizes::Header),
+        (b"RF64", b"WAVE") | (b"BW64", b"WAVE") => Ok(Sizes::Ds64),
+        _ => Err(format_error("not a RIFF WAVE file")),
+    }
+}
+
+/// Reads the chunks of a RIFF or IFF file once `check` accepts its 12-byte header,
+/// which holds the container id, its size and the form type.
+pub fn read_form<R, F>(reader: &mut R, order: ByteOrder, check: F) -> error::Result<Vec<Chunk>>
+    where R: Read + Seek, F: FnOnce(&[u8; RIFF_HEADER_LEN as usize]) -> error::Result<Sizes> {
+
+    read_form_with(reader, order, check, false)
+}
+
+fn read_form_with<R, F>(reader: &mut R, order: ByteOrder, check: F, recover: bool) -> error::Result<Vec<Chunk>>
+    where R: Read + Seek, F: FnOnce(&[u8; RIFF_HEADER_LEN as usize]) -> error::Result<Sizes> {
+
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut header = [0u8; RIFF_HEADER_LEN as usize];
+    read_exact_or_format(reader, &mut header, "not a RIFF file")?;
+
+    let ds64 = match check(&header)? {
+        Sizes::Header => None,
+        Sizes::Ds64 => Some(read_ds64(reader)?),
+    };
+
+    let riff_len = match (order.u32(&header[4..8]), &ds64) {
+        (UNKNOWN_SIZE, Some(ds64)) => ds64.riff,
+        (len, _) => u64::from(len),
+    };
+
+    let end = if recover { file_len } else { CHUNK_HEADER_LEN.saturating_add(riff_len).min(file_len) };
+
+    let mut chunks = Vec::new();
+    let mut offset = RIFF_HEADER_LEN;
+
+    while offset + CHUNK_HEADER_LEN <= end {
+        let mut chunk_header = [0u8; CHUNK_HEADER_LEN as usize];
+        reader.seek(SeekFrom::Start(offset))?;
+        read_exact_or_format(reader, &mut chunk_header, "truncated chunk header")?;
+
+        let id = [chunk_header[0], chunk_header[1], chunk_header[2], chunk_header[3]];
+
+        let len = match (order.u32(&chunk_header[4..8]), &ds64) {
+            (UNKNOWN_SIZE, Some(ds64)) => ds64.len(&id)?,
+            (UNKNOWN_SIZE, None) if recover && &id == b"data" => 0,
+            (len, _) => u64::from(len),
+        };
+
+        let mut chunk = Chunk { id, offset, len };
+
+        if recover && &chunk.id == b"data" && (chunk.len == 0 || !chunk.fits(file_len)) {
+            chunk.len = file_len - chunk.payload().start;
+            chunks.push(chunk);
+            break;
+        }
+
+        if !chunk.fits(file_len) {
+            if recover && chunks.iter().any(|chunk| &chunk.id == b"data") {
+                break;
+            }
+
+            return Err(format_error("chunk extends past the end of the file"));
+        }
+
+        // chunks are word aligned, the pad byte is not counted in the chunk length
+        offset = chunk.payload().end + (chunk.len & 1);
+        chunks.push(chunk);
+    }
+
+    Ok(chunks)
+}
+
+/// Reads the `ds64` chunk, which must directly follow the form header.
+fn read_ds64<R: Read>(reader: &mut R) -> error::Result<Ds64> {
+    let mut chunk_header = [0u8; CHUNK_HEADER_LEN as usize];
+    read_exact_or_format(reader, &mut chunk_header, "missing ds64 chunk")?;
+
+    let len = ByteOrder::Little.u32(&chunk_header[4..8]) as usize;
+
+    if &chunk_header[0..4] != b"ds64" || len < DS64_LEN {
+        return Err(format_error("missing ds64 chunk"));
+    }
+
+    let mut payload = vec![0u8; len];
+    read_exact_or_format(reader, &mut payload, "truncated ds64 chunk")?;
+
+    // the sample count at 16..24 is not needed, as it follows from the data size
+    let table_len = ByteOrder::Little.u32(&payload[24..28]) as usize;
+
+    let table = payload[DS64_LEN..].chunks_exact(DS64_ENTRY_LEN)
+        .take(table_len)
+        .map(|entry| ([entry[0], entry[1], entry[2], entry[3]], ByteOrder::Little.u64(&entry[4..12])))
+        .collect();
+
+    Ok(Ds64 {
+        riff: ByteOrder::Little.u64(&payload[0..8]),
+        data: ByteOrder::Little.u64(&payload[8..16]),
+        table,
+    })
+}
+
+impl Ds64 {
+    fn len(&self, id: &[u8; 4]) -> error::Result<u64> {
+        if id == b"data" {
+            return Ok(self.data);
+        }
+
+        self.table.iter()
+            .find(|&(table_id, _)| table_id == id)
+            .map(|&(_, len)| len)
+            .ok_or_else(|| format_error("chunk size missing from the ds64 chunk"))
+    }
+}
+
+/// Reads the whole payload of a chunk.
+pub fn read_payload<R: Read + Seek>(reader: &mut R, chunk: &Chunk) -> error::Result<Vec<u8>> {
+    let mut payload = vec![0u8; chunk.len as usize];
+    reader.seek(SeekFrom::Start(chunk.payload().start))?;
+    read_exact_or_format(reader, &mut payload, "truncated chunk")?;
+    Ok(payload)
+}
+
+pub fn find<'a>(chunks: &'a [Chunk], id: &[u8; 4]) -> error::Result<&'a Chunk> {
+    chunks.iter()
+        .find(|chunk| &chunk.id == id)
+        .ok_or_else(|| format_error("missing chunk"))
+}
+
+fn read_exact_or_format<R: Read>(reader: &mut R, buf: &mut [u8], msg: &'static str) -> error::Result<()> {
+    match reader.read_exact(buf) {
+        Ok(_) => Ok(()),
+        Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => Err(format_error(msg)),
+        Err(e) => Err(Error::from(e)),
+    }
+}
+
+pub fn format_error(msg: &'static str) -> Error {
+    Error::format(msg)
+}
+
+impl ByteOrder {
+    pub fn u16(self, bytes: &[u8]) -> u16 {
+        match self {
+            ByteOrder::Little => u16::from(bytes[0]) | u16::from(bytes[1]) << 8,
+            ByteOrder::Big => u16::from(bytes[0]) << 8 | u16::from(bytes[1]),
+        }
+    }
+
+    pub fn u32(self, bytes: &[u8]) -> u32 {
+        match self {
+            ByteOrder::Little => u32::from(self.u16(&bytes[0..2])) | u32::from(self.u16(&bytes[2..4])) << 16,
+            ByteOrder::Big => u32::from(self.u16(&bytes[0..2])) << 16 | u32::from(self.u16(&bytes[2..4])),
+        }
+    }
+
+    pub fn u64(self, bytes: &[u8]) -> u64 {
+        match self {
+            ByteOrder::Little => u64::from(self.u32(&bytes[0..4])) | u64::from(self.u32(&bytes[4..8])) << 32,
+            ByteOrder::Big => u64::from(self.u32(&bytes[0..4])) << 32 | u64::from(self.u32(&bytes[4..8])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::error::ErrorKind;
+    use std::io::Cursor;
+
+    #[test]
+    pub fn test_read_chunks_with_padding() {
+        let data = b"RIFF\x1A\x00\x00\x00WAVEodd \x03\x00\x00\x00abc\x00data\x02\x00\x00\x00\x01\x02".to_vec();
+        let chunks = read_chunks(&mut Cursor::new(data)).unwrap();
+
+        assert_eq!(2, chunks.len());
+        assert_eq!(b"odd ", &chunks[0].id);
+        assert_eq!(20..23, chunks[0].payload());
+        assert_eq!(b"data", &chunks[1].id);
+        assert_eq!(32..34, chunks[1].payload());
+    }
+
+    #[test]
+    pub fn test_read_chunks_truncated() {
+        let data = b"RIFF\x10\x00\x00\x00WAVEdata\x08\x00\x00\x00\x01\x02".to_vec();
+        assert!(read_chunks(&mut Cursor::new(data)).is_err());
+    }
+
+    #[test]
+    pub fn test_read_chunks_ds64() {
+        let mut data = b"RF64\xFF\xFF\xFF\xFFWAVEds64\x28\x00\x00\x00".to_vec();
+        data.extend_from_slice(&74u64.to_le_bytes());
+        data.extend_from_slice(&2u64.to_le_bytes());
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(b"big ");
+        data.extend_from_slice(&3u64.to_le_bytes());
+        data.extend_from_slice(b"big \xFF\xFF\xFF\xFFabc\x00data\xFF\xFF\xFF\xFF\x01\x02");
+
+        let chunks = read_chunks(&mut Cursor::new(&data)).unwrap();
+
+        assert_eq!(3, chunks.len());
+        assert_eq!(b"ds64", &chunks[0].id);
+        assert_eq!(b"big ", &chunks[1].id);
+        assert_eq!(68..71, chunks[1].payload());
+        assert_eq!(b"data", &chunks[2].id);
+        assert_eq!(80..82, chunks[2].payload());
+
+        // without its ds64 chunk, the sizes are unknown
+        let mut riff = b"RF64\xFF\xFF\xFF\xFFWAVE".to_vec();
+        riff.extend_from_slice(&data[60..]);
+        assert!(read_chunks(&mut Cursor::new(riff)).is_err());
+    }
+
+    #[test]
+    pub fn test_read_chunks_ds64_overflow() {
+        let mut data = b"RF64\xFF\xFF\xFF\xFFWAVEds64\x1C\x00\x00\x00".to_vec();
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(b"data\xFF\xFF\xFF\xFF\x01\x02");
+
+        let e = read_chunks(&mut Cursor::new(&data)).unwrap_err();
+        let chunks = recover_chunks(&mut Cursor::new(&data)).unwrap();
+
+        assert!(matches!(*e.kind(), ErrorKind::Format(_)));
+        assert_eq!(2, chunks.len());
+        assert_eq!(b"data", &chunks[1].id);
+        assert_eq!(56..58, chunks[1].payload());
+    }
+}
@@ -0,0 +1,71 @@
+//! Helpers shared by the tests of the fillers.
+
+use super::error;
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Writes `data` into the file, runs `fill` on it and returns the result along with the file content
+/// afterwards, removing the file.
+pub fn fill_file<F>(file_path: &str, data: &[u8], fill: F) -> (error::Result<()>, Vec<u8>)
+    where F: FnOnce(&Path) -> error::Result<()> {
+
+    File::create(file_path).unwrap().write_all(data).unwrap();
+
+    let res = fill(Path::new(file_path));
+
+    let mut filled = Vec::new();
+    File::open(file_path).unwrap().read_to_end(&mut filled).unwrap();
+    fs::remove_file(file_path).unwrap();
+
+    (res, filled)
+}
+
+// 44100 Hz as an 80-bit extended precision number
+const RATE_44100: [u8; 10] = [0x40, 0x0E, 0xAC, 0x44, 0, 0, 0, 0, 0, 0];
+
+fn chunk(id: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut chunk = id.to_vec();
+    chunk.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    chunk.extend_from_slice(payload);
+
+    if payload.len() % 2 == 1 {
+        chunk.push(0);
+    }
+
+    chunk
+}
+
+/// An AIFF-C file with 3 stereo frames, when `compression` is given, otherwise an AIFF file.
+/// The SSND chunk has 2 bytes of offset padding before the frames and 2 bytes of block padding after.
+pub fn aiff_vec(compression: Option<&[u8; 4]>, bits: u16, sample_len: usize) -> Vec<u8> {
+    let mut comm = Vec::new();
+    comm.extend_from_slice(&2u16.to_be_bytes());
+    comm.extend_from_slice(&3u32.to_be_bytes());
+    comm.extend_from_slice(&bits.to_be_bytes());
+    comm.extend_from_slice(&RATE_44100);
+
+    if let Some(compression) = compression {
+        comm.extend_from_slice(compression);
+        comm.extend_from_slice(b"\x03abc");
+    }
+
+    let mut ssnd = Vec::new();
+    ssnd.extend_from_slice(&2u32.to_be_bytes());
+    ssnd.extend_from_slice(&0u32.to_be_bytes());
+    ssnd.extend_from_slice(b"\xAA\xAA");
+    ssnd.extend((0..3 * 2 * sample_len).map(|i| i as u8 | 1));
+    ssnd.extend_from_slice(b"\xBB\xBB");
+
+    let mut body = if compression.is_some() { b"AIFC".to_vec() } else { b"AIFF".to_vec() };
+    body.extend(chunk(b"COMM", &comm));
+    body.extend(chunk(b"NAME", b"odd"));
+    body.extend(chunk(b"SSND", &ssnd));
+    body.extend(chunk(b"ANNO", b"kept"));
+
+    let mut form = b"FORM".to_vec();
+    form.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    form.extend(body);
+    form
+}
@@ -0,0 +1,271 @@
+use super::error::{self, Error, ErrorKind, Operation, ResultExt};
+use super::plan::Plan;
+use super::registry::{self, FileFiller};
+use super::FillOptions;
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The file size differs from the planned one.
+    Len { expected: u64, actual: u64 },
+    /// The region still holds bytes other than its fill byte.
+    NotFilled { range: Range<u64>, fill: u8, first_offset: u64, count: u64 },
+    /// The file structure differs from the one before filling, or no longer parses.
+    Structure { expected: String, actual: String },
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Mismatch::Len { expected, actual } =>
+                write!(f, "expected {} bytes, found {}", expected, actual),
+
+            Mismatch::NotFilled { ref range, fill, first_offset, count } =>
+                write!(f, "{} bytes in {}..{} are not 0x{:02X}, the first at offset {}",
+                    count, range.start, range.end, fill, first_offset),
+
+            Mismatch::Structure { ref expected, ref actual } =>
+                write!(f, "expected {}, found {}", expected, actual),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Verification {
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Verification {
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl Display for Verification {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "verified");
+        }
+
+        for (i, mismatch) in self.mismatches.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+
+            write!(f, "{}", mismatch)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Verifies the file against a plan made before it was filled, using the filler named in the plan.
+pub fn verify<P: AsRef<Path>>(file_path: P, plan: &Plan) -> error::Result<Verification> {
+    let file_path = file_path.as_ref();
+
+    let filler = registry::global().read().unwrap_or_else(|e| e.into_inner()).get(&plan.filler);
+
+    match filler {
+        Some(filler) => filler.verify(file_path, plan).for_path(file_path),
+        None => verify_regions(file_path, plan),
+    }
+}
+
+/// Checks that the file has the planned size and that every planned region holds only its fill byte.
+pub fn verify_regions<P: AsRef<Path>>(file_path: P, plan: &Plan) -> error::Result<Verification> {
+    let file_path = file_path.as_ref();
+    read_regions(file_path, plan).during(Operation::Read).for_path(file_path)
+}
+
+fn read_regions(file_path: &Path, plan: &Plan) -> error::Result<Verification> {
+    let mut file = BufReader::new(File::open(file_path).during(Operation::Open)?);
+    let len = file.get_ref().metadata()?.len();
+
+    let mut verification = Verification::default();
+
+    if len != plan.len {
+        verification.mismatches.push(Mismatch::Len { expected: plan.len, actual: len });
+    }
+
+    let mut buf = vec![0u8; 64 * 1024];
+
+    for region in &plan.regions {
+        let end = region.range.end.min(len);
+        let mut offset = region.range.start.min(end);
+        let mut first_offset = None;
+        let mut count = 0;
+
+        file.seek(SeekFrom::Start(offset))?;
+
+        while offset < end {
+            let n = (end - offset).min(buf.len() as u64) as usize;
+            file.read_exact(&mut buf[..n])?;
+
+            for (i, &b) in buf[..n].iter().enumerate() {
+                if b != region.fill {
+                    first_offset = first_offset.or(Some(offset + i as u64));
+                    count += 1;
+                }
+            }
+
+            offset += n as u64;
+        }
+
+        if let Some(first_offset) = first_offset {
+            verification.mismatches.push(Mismatch::NotFilled {
+                range: region.range.clone(),
+                fill: region.fill,
+                first_offset,
+                count,
+            });
+        }
+    }
+
+    Ok(verification)
+}
+
+/// Verifies by planning the filled file again, which must parse into the same structure and regions,
+/// before checking the regions themselves.
+pub fn verify_replanned<P, F>(file_path: P, plan: &Plan, replan: F) -> error::Result<Verification>
+    where P: AsRef<Path>, F: FnOnce(&Path) -> error::Result<Plan> {
+
+    let file_path = file_path.as_ref();
+
+    let replanned = match replan(file_path) {
+        Ok(replanned) => replanned,
+
+        Err(e) => {
+            if e.is_io() {
+                return Err(e);
+            }
+
+            let mismatch = Mismatch::Structure {
+                expected: plan.structure.clone().unwrap_or_default(),
+                actual: e.kind().to_string(),
+            };
+
+            return Ok(Verification { mismatches: vec![mismatch] });
+        },
+    };
+
+    let mut verification = verify_regions(file_path, plan)?;
+
+    if replanned.structure != plan.structure || replanned.regions != plan.regions {
+        verification.mismatches.push(Mismatch::Structure {
+            expected: describe(plan),
+            actual: describe(&replanned),
+        });
+    }
+
+    Ok(verification)
+}
+
+/// Fills the file using the filler, verifying the result if `options.verify` is set.
+pub fn fill_verified(filler: &dyn FileFiller, file_path: &Path, options: &FillOptions) -> error::Result<()> {
+    let fill = || {
+        if !options.verify {
+            return filler.fill(file_path, options);
+        }
+
+        let plan = filler.plan(file_path, options)?;
+        filler.fill(file_path, options)?;
+
+        let verification = filler.verify(file_path, &plan)?;
+
+        if verification.is_ok() {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::Verify(verification)))
+        }
+    };
+
+    fill().for_path(file_path)
+}
+
+fn describe(plan: &Plan) -> String {
+    let regions: Vec<_> = plan.regions.iter()
+        .map(|region| format!("{}..{}", region.range.start, region.range.end))
+        .collect();
+
+    match plan.structure {
+        Some(ref structure) => format!("{} with data at {}", structure, regions.join(", ")),
+        None => format!("data at {}", regions.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::plan::Region;
+    use std::fs;
+    use std::io::Write;
+
+    struct NoopFiller;
+
+    impl FileFiller for NoopFiller {
+        fn name(&self) -> &str {
+            "noop"
+        }
+
+        fn detect(&self, _: &Path) -> bool {
+            true
+        }
+
+        fn fill(&self, _: &Path, _: &FillOptions) -> error::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    pub fn test_verify_regions() {
+        let file_path = "test_verify_regions.bin";
+        File::create(file_path).unwrap().write_all(b"\x00\x01\x00\x02\x80\x80").unwrap();
+
+        let plan = Plan {
+            filler: "any".to_owned(),
+            regions: vec![Region { range: 0..4, fill: 0 }, Region { range: 4..6, fill: 0x80 }],
+            len: 8,
+            structure: None,
+        };
+
+        let verification = verify_regions(file_path, &plan).unwrap();
+        fs::remove_file(file_path).unwrap();
+
+        assert!(!verification.is_ok());
+        assert_eq!(vec![
+            Mismatch::Len { expected: 8, actual: 6 },
+            Mismatch::NotFilled { range: 0..4, fill: 0, first_offset: 1, count: 2 },
+        ], verification.mismatches);
+    }
+
+    #[test]
+    pub fn test_fill_verified_catches_noop() {
+        let file_path = "test_fill_verified_catches_noop.bin";
+        File::create(file_path).unwrap().write_all(b"not zero").unwrap();
+
+        let options = FillOptions {
+            verify: true,
+            ..FillOptions::default()
+        };
+
+        let res = fill_verified(&NoopFiller, Path::new(file_path), &options);
+        let unverified = fill_verified(&NoopFiller, Path::new(file_path), &FillOptions::default());
+        fs::remove_file(file_path).unwrap();
+
+        let e = res.unwrap_err();
+
+        match *e.kind() {
+            ErrorKind::Verify(ref verification) => assert_eq!(1, verification.mismatches.len()),
+            ref other => panic!("expected a verification failure, got {:?}", other),
+        }
+
+        assert_eq!(Some(Path::new(file_path)), e.path());
+
+        assert!(unverified.is_ok());
+    }
+}
@@ -0,0 +1,272 @@
+use super::error::{self, Error, Operation};
+use super::{fill_matching_with, FillOptions};
+use glob::Pattern;
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Fills the targets of symbolic links, descending into linked directories.
+    Follow,
+    /// Leaves symbolic links alone.
+    Skip,
+    /// Reports every symbolic link as a failure.
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct WalkOptions {
+    /// Only files matching one of these patterns are filled, unless empty.
+    /// Patterns are matched against the path relative to the root.
+    pub include: Vec<Pattern>,
+    /// Files and directories matching any of these patterns are left alone.
+    pub exclude: Vec<Pattern>,
+    pub symlinks: SymlinkPolicy,
+}
+
+impl Default for WalkOptions {
+    fn default() -> WalkOptions {
+        WalkOptions {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            symlinks: SymlinkPolicy::Skip,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Outcome for every file that was filled, and for every path that could not be walked.
+    pub results: Vec<(PathBuf, error::Result<()>)>,
+    /// Symbolic links and special files that were left alone.
+    pub skipped: Vec<PathBuf>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.results.iter().all(|(_, res)| res.is_ok())
+    }
+
+    pub fn failures(&self) -> Vec<(&Path, &Error)> {
+        self.results.iter()
+            .filter_map(|(path, res)| res.as_ref().err().map(|e| (path.as_path(), e)))
+            .collect()
+    }
+}
+
+/// Applies `fill_matching` to every file under `root`.
+pub fn fill_dir<P: AsRef<Path>>(root: P, walk_options: &WalkOptions, options: &FillOptions) -> Report {
+    fill_dir_with(root, walk_options, |file_path| fill_matching_with(file_path, options))
+}
+
+/// Applies `fill` to every file under `root`, carrying on past failures.
+pub fn fill_dir_with<P, F>(root: P, walk_options: &WalkOptions, mut fill: F) -> Report
+    where P: AsRef<Path>, F: FnMut(&Path) -> error::Result<()> {
+
+    let root = root.as_ref();
+
+    let mut walker = Walker {
+        root,
+        options: walk_options,
+        visited: HashSet::new(),
+        report: Report::default(),
+    };
+
+    match fs::metadata(root) {
+        Ok(ref metadata) if metadata.is_dir() => walker.visit_dir(root, &mut fill),
+        Ok(_) => walker.report.results.push((root.to_owned(), fill(root))),
+        Err(e) => walker.report.results.push((root.to_owned(), Err(failure(root, e, Operation::Open)))),
+    }
+
+    walker.report
+}
+
+struct Walker<'a> {
+    root: &'a Path,
+    options: &'a WalkOptions,
+    visited: HashSet<PathBuf>,
+    report: Report,
+}
+
+impl<'a> Walker<'a> {
+    fn visit_dir<F: FnMut(&Path) -> error::Result<()>>(&mut self, dir: &Path, fill: &mut F) {
+        // guards against symbolic link cycles when following links
+        match fs::canonicalize(dir) {
+            Ok(canonical) => if !self.visited.insert(canonical) {
+                return;
+            },
+
+            Err(e) => return self.report.results.push((dir.to_owned(), Err(failure(dir, e, Operation::ListDir)))),
+        }
+
+        let paths = match read_dir_sorted(dir) {
+            Ok(paths) => paths,
+            Err(e) => return self.report.results.push((dir.to_owned(), Err(failure(dir, e, Operation::ListDir)))),
+        };
+
+        for path in paths {
+            self.visit(&path, fill);
+        }
+    }
+
+    fn visit<F: FnMut(&Path) -> error::Result<()>>(&mut self, path: &Path, fill: &mut F) {
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => return self.report.results.push((path.to_owned(), Err(failure(path, e, Operation::Open)))),
+        };
+
+        let metadata = if metadata.file_type().is_symlink() {
+            match self.options.symlinks {
+                SymlinkPolicy::Skip => return self.report.skipped.push(path.to_owned()),
+
+                SymlinkPolicy::Error => {
+                    let e = io::Error::other("symbolic links are not allowed");
+                    return self.report.results.push((path.to_owned(), Err(failure(path, e, Operation::Open))));
+                },
+
+                SymlinkPolicy::Follow => match fs::metadata(path) {
+                    Ok(metadata) => metadata,
+                    Err(e) => return self.report.results.push((path.to_owned(), Err(failure(path, e, Operation::Open)))),
+                },
+            }
+        } else {
+            metadata
+        };
+
+        let relative = path.strip_prefix(self.root).unwrap_or(path);
+
+        if self.options.exclude.iter().any(|pattern| pattern.matches_path(relative)) {
+            return;
+        }
+
+        if metadata.is_dir() {
+            self.visit_dir(path, fill);
+        } else if !metadata.is_file() {
+            self.report.skipped.push(path.to_owned());
+        } else if self.options.include.is_empty() || self.options.include.iter().any(|pattern| pattern.matches_path(relative)) {
+            let res = fill(path);
+            self.report.results.push((path.to_owned(), res));
+        }
+    }
+}
+
+fn failure(path: &Path, e: io::Error, operation: Operation) -> Error {
+    Error::from(e).with_operation(operation).with_path(path)
+}
+
+fn read_dir_sorted(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::File;
+    use std::io::{Read, Write};
+
+    fn write_file<P: AsRef<Path>>(file_path: P) {
+        File::create(file_path).unwrap().write_all(b"not zero").unwrap();
+    }
+
+    fn is_zero_filled<P: AsRef<Path>>(file_path: P) -> bool {
+        let mut buf = Vec::new();
+        File::open(file_path).unwrap().read_to_end(&mut buf).unwrap();
+        buf.into_iter().all(|d| d == 0)
+    }
+
+    #[test]
+    pub fn test_fill_dir_filters() {
+        let root = Path::new("test_fill_dir_filters");
+        fs::create_dir_all(root.join("sub/skip")).unwrap();
+        write_file(root.join("a.bin"));
+        write_file(root.join("a.txt"));
+        write_file(root.join("sub/b.bin"));
+        write_file(root.join("sub/skip/c.bin"));
+
+        let walk_options = WalkOptions {
+            include: vec![Pattern::new("*.bin").unwrap()],
+            exclude: vec![Pattern::new("sub/skip").unwrap()],
+            ..WalkOptions::default()
+        };
+
+        let report = fill_dir(root, &walk_options, &FillOptions::default());
+
+        let filled: Vec<_> = report.results.iter().map(|(path, _)| path.clone()).collect();
+        let zeroed = [is_zero_filled(root.join("a.bin")), is_zero_filled(root.join("sub/b.bin"))];
+        let untouched = [is_zero_filled(root.join("a.txt")), is_zero_filled(root.join("sub/skip/c.bin"))];
+
+        fs::remove_dir_all(root).unwrap();
+
+        assert!(report.is_ok());
+        assert_eq!(vec![root.join("a.bin"), root.join("sub/b.bin")], filled);
+        assert_eq!([true, true], zeroed);
+        assert_eq!([false, false], untouched);
+    }
+
+    #[test]
+    pub fn test_fill_dir_collects_failures() {
+        let root = Path::new("test_fill_dir_collects_failures");
+        fs::create_dir_all(root).unwrap();
+        write_file(root.join("a.wav"));
+        write_file(root.join("b.bin"));
+
+        let report = fill_dir(root, &WalkOptions::default(), &FillOptions::default());
+        let b_zeroed = is_zero_filled(root.join("b.bin"));
+
+        fs::remove_dir_all(root).unwrap();
+
+        // the invalid WAV file must not stop the rest from being filled
+        assert_eq!(2, report.results.len());
+        assert_eq!(1, report.failures().len());
+        assert_eq!(root.join("a.wav"), report.failures()[0].0);
+        assert!(b_zeroed);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn test_fill_dir_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let root = Path::new("test_fill_dir_symlinks");
+        let target = Path::new("test_fill_dir_symlinks_target");
+        fs::create_dir_all(root).unwrap();
+        fs::create_dir_all(target).unwrap();
+        write_file(target.join("a.bin"));
+        symlink(fs::canonicalize(target).unwrap(), root.join("linked")).unwrap();
+        symlink(fs::canonicalize(root).unwrap(), root.join("cycle")).unwrap();
+
+        let fill_with = |symlinks| {
+            let walk_options = WalkOptions {
+                symlinks,
+                ..WalkOptions::default()
+            };
+
+            fill_dir(root, &walk_options, &FillOptions::default())
+        };
+
+        let skip = fill_with(SymlinkPolicy::Skip);
+        let error = fill_with(SymlinkPolicy::Error);
+        let skipped_untouched = !is_zero_filled(target.join("a.bin"));
+        let follow = fill_with(SymlinkPolicy::Follow);
+        let followed_zeroed = is_zero_filled(target.join("a.bin"));
+
+        fs::remove_dir_all(root).unwrap();
+        fs::remove_dir_all(target).unwrap();
+
+        assert!(skip.results.is_empty());
+        assert_eq!(2, skip.skipped.len());
+        assert_eq!(2, error.failures().len());
+        assert!(skipped_untouched);
+        assert!(follow.is_ok());
+        assert_eq!(vec![root.join("linked/a.bin")], follow.results.iter().map(|(path, _)| path.clone()).collect::<Vec<_>>());
+        assert!(followed_zeroed);
+    }
+}
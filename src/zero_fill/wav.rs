@@ -0,0 +1,236 @@
+use super::error::{self, Error, ErrorKind};
+use super::riff::{self, ByteOrder};
+use super::{Patch, Recovery};
+
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+
+const FMT_LEN: usize = 16;
+/// The size of the `fmt ` chunk of WAVE_FORMAT_EXTENSIBLE, up to and including the sub-format GUID.
+const FMT_EXTENSIBLE_LEN: usize = 40;
+/// The payload of the `ds64` chunk, which directly follows the 12-byte RF64 or BW64 header.
+const DS64_PAYLOAD_OFFSET: u64 = 20;
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_ALAW: u16 = 0x0006;
+const WAVE_FORMAT_MULAW: u16 = 0x0007;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// The sub-format GUIDs that embed a format tag in their first two bytes, without those bytes.
+const SUB_FORMAT_SUFFIXES: [[u8; 14]; 2] = [
+    // KSDATAFORMAT_SUBTYPE_PCM, KSDATAFORMAT_SUBTYPE_IEEE_FLOAT and so on
+    [0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71],
+    // the ambisonic B-format sub-types
+    [0x00, 0x00, 0x21, 0x07, 0xD3, 0x11, 0x86, 0x44, 0xC8, 0xC1, 0xCA, 0x00, 0x00, 0x00],
+];
+
+/// The fields of the `fmt ` chunk that are needed to find and silence the samples.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fmt {
+    /// The format tag, or for WAVE_FORMAT_EXTENSIBLE, the format tag embedded in its sub-format GUID.
+    pub format_tag: u16,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub block_align: u16,
+    /// The container size of a sample, which may hold fewer valid bits.
+    pub bits: u16,
+    pub extensible: Option<Extensible>,
+}
+
+/// The fields that WAVE_FORMAT_EXTENSIBLE adds to the `fmt ` chunk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Extensible {
+    pub valid_bits: u16,
+    pub channel_mask: u32,
+    pub sub_format: [u8; 16],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wav {
+    /// `RIFF`, or `RF64` or `BW64` for files whose sizes are in a `ds64` chunk.
+    pub container: [u8; 4],
+    pub fmt: Fmt,
+    /// Byte range of the payload of the `data` chunk.
+    pub data: Range<u64>,
+    pub silence: u8,
+    /// Whether the sizes in the headers were wrong, and the data was taken to run to the end of the file.
+    pub recovered: bool,
+}
+
+impl Wav {
+    /// Number of samples over all channels, as counted by hound.
+    pub fn samples(&self) -> u64 {
+        let sample_len = u64::from(self.fmt.block_align / self.fmt.channels).max(1);
+        (self.data.end - self.data.start) / sample_len
+    }
+}
+
+pub fn read_wav<R: Read + Seek>(reader: &mut R, recovery: Recovery) -> error::Result<Wav> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    let recover = recovery != Recovery::Strict;
+
+    // recovering is only tried on files that look unfinished, as a `data` size of 0 is valid when it is
+    let (chunks, recovered) = match riff::read_chunks(reader) {
+        Ok(ref chunks) if recover && is_unfinished(chunks, file_len) => (riff::recover_chunks(reader)?, true),
+        Ok(chunks) => (chunks, false),
+
+        Err(ref e) if recover && matches!(*e.kind(), ErrorKind::Format(_)) => (riff::recover_chunks(reader)?, true),
+        Err(e) => return Err(e),
+    };
+
+    let mut container = [0u8; 4];
+    reader.seek(SeekFrom::Start(0))?;
+    reader.read_exact(&mut container)?;
+
+    let fmt = read_fmt(&riff::read_payload(reader, riff::find(&chunks, b"fmt ")?)?)?;
+    let data = riff::find(&chunks, b"data")?.payload();
+    let silence = silence(&fmt)?;
+
+    Ok(Wav { container, fmt, data, silence, recovered })
+}
+
+/// Returns the writes that correct the RIFF and `data` sizes of a recovered file of `file_len` bytes.
+pub fn size_repairs(wav: &Wav, file_len: u64) -> error::Result<Vec<Patch>> {
+    if !wav.recovered {
+        return Ok(Vec::new());
+    }
+
+    let le = |n: u64, len: usize| n.to_le_bytes()[..len].to_vec();
+    let data_len = wav.data.end - wav.data.start;
+
+    if &wav.container != b"RIFF" {
+        // the sizes in the RF64 and BW64 headers stay 0xFFFFFFFF, the ds64 chunk right after them holds
+        // the RIFF size, the data size and the number of sample frames
+        return Ok(vec![
+            Patch { offset: DS64_PAYLOAD_OFFSET, bytes: le(file_len - 8, 8) },
+            Patch { offset: DS64_PAYLOAD_OFFSET + 8, bytes: le(data_len, 8) },
+            Patch { offset: DS64_PAYLOAD_OFFSET + 16, bytes: le(data_len / u64::from(wav.fmt.block_align), 8) },
+            Patch { offset: wav.data.start - 4, bytes: le(0xFFFF_FFFF, 4) },
+        ]);
+    }
+
+    if file_len - 8 > u64::from(u32::MAX) {
+        return Err(Error::unsupported("repairing the sizes of a RIFF file over 4 GiB"));
+    }
+
+    Ok(vec![
+        Patch { offset: 4, bytes: le(file_len - 8, 4) },
+        Patch { offset: wav.data.start - 4, bytes: le(data_len, 4) },
+    ])
+}
+
+/// Returns whether the chunks lack a `data` chunk, or end with a `data` chunk that is followed by more bytes
+/// than its sizes account for, as when a recording was cut short.
+fn is_unfinished(chunks: &[riff::Chunk], file_len: u64) -> bool {
+    match chunks.iter().position(|chunk| &chunk.id == b"data") {
+        Some(i) => i + 1 == chunks.len() && chunks[i].payload().end + (chunks[i].len & 1) < file_len,
+        None => true,
+    }
+}
+
+fn read_fmt(payload: &[u8]) -> error::Result<Fmt> {
+    if payload.len() < FMT_LEN {
+        return Err(riff::format_error("truncated fmt chunk"));
+    }
+
+    let mut format_tag = ByteOrder::Little.u16(&payload[0..2]);
+
+    let extensible = if format_tag == WAVE_FORMAT_EXTENSIBLE {
+        Some(read_extensible(payload)?)
+    } else {
+        None
+    };
+
+    if let Some(ref extensible) = extensible {
+        // a known sub-format GUID starts with the format tag it stands for, unknown ones stay extensible
+        if SUB_FORMAT_SUFFIXES.iter().any(|suffix| extensible.sub_format[2..] == suffix[..]) {
+            format_tag = ByteOrder::Little.u16(&extensible.sub_format[0..2]);
+        }
+    }
+
+    let fmt = Fmt {
+        format_tag,
+        channels: ByteOrder::Little.u16(&payload[2..4]),
+        sample_rate: ByteOrder::Little.u32(&payload[4..8]),
+        block_align: ByteOrder::Little.u16(&payload[12..14]),
+        bits: ByteOrder::Little.u16(&payload[14..16]),
+        extensible,
+    };
+
+    if fmt.channels == 0 || fmt.block_align == 0 {
+        return Err(riff::format_error("invalid fmt chunk"));
+    }
+
+    Ok(fmt)
+}
+
+fn read_extensible(payload: &[u8]) -> error::Result<Extensible> {
+    if payload.len() < FMT_EXTENSIBLE_LEN || usize::from(ByteOrder::Little.u16(&payload[16..18])) < FMT_EXTENSIBLE_LEN - 18 {
+        return Err(riff::format_error("truncated WAVE_FORMAT_EXTENSIBLE fmt chunk"));
+    }
+
+    let mut sub_format = [0u8; 16];
+    sub_format.copy_from_slice(&payload[24..40]);
+
+    Ok(Extensible {
+        valid_bits: ByteOrder::Little.u16(&payload[18..20]),
+        channel_mask: ByteOrder::Little.u32(&payload[20..24]),
+        sub_format,
+    })
+}
+
+/// Returns the byte that fills the samples with silence.
+fn silence(fmt: &Fmt) -> error::Result<u8> {
+    match (fmt.format_tag, fmt.bits) {
+        // 8-bit PCM samples are unsigned and centered at 0x80
+        (WAVE_FORMAT_PCM, 8) => Ok(0x80),
+        (WAVE_FORMAT_PCM, 16) | (WAVE_FORMAT_PCM, 24) | (WAVE_FORMAT_PCM, 32) => Ok(0),
+        (WAVE_FORMAT_IEEE_FLOAT, 32) | (WAVE_FORMAT_IEEE_FLOAT, 64) => Ok(0),
+        // the G.711 codewords for a zero sample, after their bit inversions
+        (WAVE_FORMAT_ALAW, 8) => Ok(0xD5),
+        (WAVE_FORMAT_MULAW, 8) => Ok(0xFF),
+        (WAVE_FORMAT_EXTENSIBLE, _) => match fmt.extensible {
+            Some(ref extensible) => Err(Error::unsupported(format!("WAV sub-format {}", guid(&extensible.sub_format)))),
+            None => Err(Error::unsupported("WAV sub-format")),
+        },
+        (_, bits) => Err(Error::unsupported(format!("{}-bit {} WAV samples", bits, format_name(fmt.format_tag)))),
+    }
+}
+
+fn format_name(format_tag: u16) -> String {
+    match format_tag {
+        WAVE_FORMAT_PCM => "integer".to_owned(),
+        WAVE_FORMAT_IEEE_FLOAT => "float".to_owned(),
+        WAVE_FORMAT_ALAW => "A-law".to_owned(),
+        WAVE_FORMAT_MULAW => "mu-law".to_owned(),
+        WAVE_FORMAT_EXTENSIBLE => "extensible".to_owned(),
+        format_tag => format!("format 0x{:04X}", format_tag),
+    }
+}
+
+/// Formats a GUID stored in the mixed-endian layout of Windows.
+fn guid(bytes: &[u8; 16]) -> String {
+    let order = ByteOrder::Little;
+    let tail: Vec<_> = bytes[10..].iter().map(|b| format!("{:02X}", b)).collect();
+
+    format!("{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{}",
+        order.u32(&bytes[0..4]), order.u16(&bytes[4..6]), order.u16(&bytes[6..8]), bytes[8], bytes[9], tail.concat())
+}
+
+pub fn describe(wav: &Wav) -> String {
+    let container = match &wav.container {
+        b"RIFF" => "WAV".to_owned(),
+        container => format!("{} WAV", String::from_utf8_lossy(container)),
+    };
+
+    // the extensible fields are part of the structure, so that verifying catches a dropped channel mask
+    let extensible = match wav.fmt.extensible {
+        Some(ref extensible) => format!(" (sub-format {}, {} valid bits, channel mask 0x{:X})",
+            guid(&extensible.sub_format), extensible.valid_bits, extensible.channel_mask),
+        None => String::new(),
+    };
+
+    format!("{} channel {}-bit {} {}{} at {} Hz with {} samples", wav.fmt.channels, wav.fmt.bits,
+        format_name(wav.fmt.format_tag), container, extensible, wav.fmt.sample_rate, wav.samples())
+}